@@ -1,9 +1,32 @@
+mod author;
 #[allow(clippy::module_inception)]
 mod blog;
 pub mod config;
+mod front_matter;
+mod history;
+mod html;
+mod markdown_renderer;
+mod page;
+mod page_metadata;
+mod permalink;
 mod post;
 mod post_metadata;
+mod slug;
+mod static_file;
+mod syntax_highlighter;
+mod taxonomy;
 
+pub use author::Author;
 pub use blog::Blog;
+pub use front_matter::FrontMatterValues;
+pub use history::Revision;
+pub use markdown_renderer::{
+    is_relative_url, Heading, MarkdownRenderer, RenderedMarkdown, ResponsiveImage, ResponsiveImages,
+};
+pub use page::Page;
+pub use page_metadata::PageMetadata;
+pub use permalink::{url_to_virtual_path, Permalink};
 pub use post::Post;
-pub use post_metadata::PostMetadata;
+pub use post_metadata::{parse_datetime, PostMetadata};
+pub use static_file::StaticFile;
+pub use taxonomy::Taxonomy;
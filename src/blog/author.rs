@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use super::{
+    config::{AuthorConfig, SlugSeparator},
+    slug::slugify,
+    Post,
+};
+
+/// An author of posts, referenced by their id in post metadata.
+#[derive(Debug, Clone)]
+pub struct Author {
+    id: String,
+    config: AuthorConfig,
+}
+
+impl Author {
+    pub fn new(id: impl Into<String>, config: AuthorConfig) -> Self {
+        Self {
+            id: id.into(),
+            config,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn config(&self) -> &AuthorConfig {
+        &self.config
+    }
+
+    /// Get the name of the author, falling back to their id.
+    pub fn name(&self) -> &str {
+        if self.config.name.trim().is_empty() {
+            &self.id
+        } else {
+            &self.config.name
+        }
+    }
+
+    /// Path of the page listing all posts of the author.
+    pub fn page_path(&self) -> PathBuf {
+        PathBuf::from(format!(
+            "authors/{}.html",
+            slugify(&self.id, SlugSeparator::default().as_char())
+        ))
+    }
+
+    /// Whether the author wrote the given post.
+    pub fn is_author_of(&self, post: &Post) -> bool {
+        post.metadata().authors.contains(&self.id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use crate::blog::config::AuthorConfig;
+
+    use super::Author;
+
+    #[test]
+    fn author_name_and_page() {
+        let author = Author::new("Jane.Doe", AuthorConfig::default());
+        assert_eq!(author.name(), "Jane.Doe");
+        assert_eq!(author.page_path(), Path::new("authors/jane_doe.html"));
+
+        let author = Author::new(
+            "jane",
+            AuthorConfig {
+                name: "Jane Doe".into(),
+                ..Default::default()
+            },
+        );
+        assert_eq!(author.name(), "Jane Doe");
+    }
+}
@@ -1,51 +1,341 @@
-use std::{borrow::Cow, fs::create_dir_all, path::Path};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs::create_dir_all,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use itertools::Itertools;
 use walkdir::WalkDir;
 
 use crate::{
     blog::{
-        config::{BlogConfig, BlogMetadataConfig, BlogThemeConfig},
-        Post,
+        config::{
+            AuthorConfig, BlogConfig, BlogMetadataConfig, BlogThemeConfig, CreatedAtFallback,
+        },
+        history,
+        post::BUNDLE_INDEX,
+        Author, MarkdownRenderer, Page, Permalink, Post, StaticFile,
     },
-    config::CommonProjectConfig,
+    config::{CommonProjectConfig, CONFIG_FILENAME},
     engine::BuildEnvironment,
     theme::{config::ThemeConfig, Theme, ThemeBundle, ThemeSource},
-    traits::{ToTheme, TryFromFile, TrySaveConfig},
+    traits::{ToTheme, TrySaveConfig},
 };
 
+const EXT_POSTS: [&str; 2] = ["md", "markdown"];
+
 #[derive(Debug)]
 pub struct Blog {
     config: BlogConfig,
     theme: Theme,
     posts: Vec<Post>,
+    pages: Vec<Page>,
+    authors: Vec<Author>,
+    static_files: Vec<StaticFile>,
+    markdown_renderer: MarkdownRenderer,
+    /// Time that scheduled and expiring posts are checked against
+    build_time: DateTime<Utc>,
 }
 
 impl Blog {
-    pub fn from_config(config: BlogConfig) -> Result<Self> {
+    pub fn from_config(
+        config: BlogConfig,
+        authors: BTreeMap<String, AuthorConfig>,
+    ) -> Result<Self> {
         log::debug!("Loading theme");
         let theme = config.theme_config.source().to_theme()?;
+        let permalink = Permalink::new(&config.permalink, config.slug_separator)?;
         let post_path = Path::new("./posts");
         log::debug!("Loading posts from {:?}", post_path);
-        let posts = Self::load_posts("posts");
+        let posts = Self::load_posts("posts", &permalink, &config)?;
+        Self::check_empty_slugs(&posts)?;
+        Self::check_duplicate_slugs(&posts)?;
+        let authors = authors
+            .into_iter()
+            .map(|(id, config)| Author::new(id, config))
+            .collect::<Vec<_>>();
+        Self::check_author_slugs(&authors)?;
+        Self::check_authors(&posts, &authors)?;
+        log::debug!("Loading pages from {:?}", Path::new("./pages"));
+        let pages = Self::load_pages("pages");
+        log::debug!("Loading static files from {:?}", Path::new("./static"));
+        let static_files = Self::load_static_files("static")?;
+        let markdown_renderer = MarkdownRenderer::new(&config)?;
         Ok(Self {
             config,
             theme,
             posts,
+            pages,
+            authors,
+            static_files,
+            markdown_renderer,
+            build_time: Utc::now(),
         })
     }
 
-    fn load_posts(path: impl AsRef<Path>) -> Vec<Post> {
+    /// Build the blog as if it was the given time, e.g. for reproducible builds.
+    #[must_use]
+    pub fn with_build_time(mut self, build_time: DateTime<Tz>) -> Self {
+        self.build_time = build_time.with_timezone(&Utc);
+        self
+    }
+
+    fn load_static_files(path: impl AsRef<Path>) -> Result<Vec<StaticFile>> {
+        let path = path.as_ref();
         WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| StaticFile::new(entry.into_path(), path))
+            .collect()
+    }
+
+    /// Load markdown files and post bundles, i.e. directories with an `index.md`.
+    ///
+    /// Posts without `created_at` get the time of the configured fallback, which is an error
+    /// if the fallback is [`CreatedAtFallback::Error`].
+    fn load_posts(
+        path: impl AsRef<Path>,
+        permalink: &Permalink,
+        config: &BlogConfig,
+    ) -> Result<Vec<Post>> {
+        let path = path.as_ref();
+        let files = WalkDir::new(path)
+            .sort_by_file_name()
             .into_iter()
             .filter_map(Result::ok)
             .filter(|entry| entry.file_type().is_file())
-            .filter_map(|entry| Post::try_from_file(entry.into_path().into()).ok())
+            .map(|entry| entry.into_path())
+            .collect::<Vec<_>>();
+        // An `index.md` directly in the posts directory is a regular post
+        let bundle_dirs = files
+            .iter()
+            .filter(|path| path.file_name() == Some(BUNDLE_INDEX.as_ref()))
+            .filter_map(|path| path.parent())
+            .filter(|dir| *dir != path)
+            .collect::<Vec<_>>();
+        // Files belong to the innermost bundle containing them
+        let bundle_of = |file: &Path| {
+            bundle_dirs
+                .iter()
+                .copied()
+                .filter(|dir| file.starts_with(dir))
+                .max_by_key(|dir| dir.components().count())
+        };
+
+        let timezone = config.timezone;
+        let mut invalid_posts = Vec::new();
+        let mut missing_created_at = Vec::new();
+        let mut created_at_fallback = |path: &Path| {
+            let created_at = match config.created_at_fallback {
+                CreatedAtFallback::ModificationTime => history::modified_at(path)?,
+                CreatedAtFallback::Git => history::first_commit_or_modified_at(path)?,
+                CreatedAtFallback::Error => {
+                    missing_created_at.push(path.to_path_buf());
+                    bail!("`created_at` is missing");
+                }
+            };
+            log::warn!(
+                "{path:?} has no `created_at`, using {}",
+                created_at.with_timezone(&timezone)
+            );
+            Ok(created_at)
+        };
+
+        let posts = files
+            .iter()
+            .filter_map(|path| {
+                let post = match bundle_of(path) {
+                    // Index of a bundle, every other file in its directory is an asset
+                    Some(dir) if path == &dir.join(BUNDLE_INDEX) => {
+                        if let Some(outer_dir) = dir.parent().and_then(bundle_of) {
+                            log::warn!(
+                                "Bundle {dir:?} is nested in bundle {outer_dir:?}, loading it as a separate post"
+                            );
+                        }
+                        let assets = files
+                            .iter()
+                            .filter(|asset| bundle_of(asset) == Some(dir) && asset != &path)
+                            .map(|asset| StaticFile::new(asset.clone(), dir))
+                            .collect::<Result<_>>();
+                        assets.and_then(|assets| {
+                            Post::from_bundle(
+                                path.into(),
+                                assets,
+                                timezone,
+                                &mut created_at_fallback,
+                            )
+                        })
+                    }
+                    Some(_) => return None,
+                    None if Self::is_markdown_file(path) => {
+                        Post::from_markdown_file(path.into(), timezone, &mut created_at_fallback)
+                    }
+                    None => {
+                        log::debug!("Ignoring {path:?}: Not a markdown file");
+                        return None;
+                    }
+                };
+                post.map(|post| post.with_permalink(permalink))
+                    .map(|post| Self::load_history(post, config))
+                    .map_err(|error| invalid_posts.push(format!("{error:#}")))
+                    .ok()
+            })
             .sorted_by(|a, b| b.metadata().created_at.cmp(&a.metadata().created_at))
+            .collect();
+
+        if !missing_created_at.is_empty() {
+            bail!(
+                "Posts must set `created_at` unless `created_at_fallback` is \"mtime\" or \"git\": {}",
+                missing_created_at
+                    .iter()
+                    .map(|path| format!("{path:?}"))
+                    .join(", ")
+            );
+        }
+        if !invalid_posts.is_empty() {
+            bail!("Unable to load posts:\n{}", invalid_posts.join("\n"));
+        }
+        Ok(posts)
+    }
+
+    /// Read the git history of a post if it is used, keeping the post without it on errors.
+    fn load_history(post: Post, config: &BlogConfig) -> Post {
+        if !config.history.uses_git() {
+            return post;
+        }
+        match history::revisions(post.path()) {
+            Ok(revisions) => post.with_history(revisions, &config.history),
+            Err(error) => {
+                log::warn!("Ignoring the history of {:?}: {error:#}", post.path());
+                post
+            }
+        }
+    }
+
+    /// Load the markdown files of standalone pages, ordered by their menu weight.
+    fn load_pages(path: impl AsRef<Path>) -> Vec<Page> {
+        let path = path.as_ref();
+        WalkDir::new(path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .filter(|page_path| {
+                let is_markdown = Self::is_markdown_file(page_path);
+                if !is_markdown {
+                    log::debug!("Ignoring {page_path:?}: Not a markdown file");
+                }
+                is_markdown
+            })
+            .filter_map(|page_path| {
+                Page::from_markdown_file(page_path.clone(), path)
+                    .map_err(|error| log::warn!("Skipping page {page_path:?}: {error:#}"))
+                    .ok()
+            })
+            .sorted_by_key(|page| page.metadata().weight)
             .collect()
     }
 
+    /// Titles without letters or digits give an empty slug, which is not a valid url.
+    fn check_empty_slugs(posts: &[Post]) -> Result<()> {
+        let paths = posts
+            .iter()
+            .filter(|post| post.slug().is_empty())
+            .map(|post| format!("{:?}", post.path()))
+            .collect::<Vec<_>>();
+        if !paths.is_empty() {
+            bail!(
+                "Posts must have a title or `slug` with letters or digits: {}",
+                paths.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Posts sharing a slug would be written to the same url, so that is an error.
+    fn check_duplicate_slugs(posts: &[Post]) -> Result<()> {
+        let duplicates = posts
+            .iter()
+            .into_group_map_by(|post| post.slug())
+            .into_iter()
+            .filter(|(_, posts)| posts.len() > 1)
+            .sorted_by_key(|(slug, _)| *slug)
+            .map(|(slug, posts)| {
+                let paths = posts
+                    .iter()
+                    .map(|post| format!("{:?}", post.path()))
+                    .join(", ");
+                format!("{slug:?} is used by {paths}")
+            })
+            .collect::<Vec<_>>();
+        if !duplicates.is_empty() {
+            bail!(
+                "Posts must have unique slugs, set a different `slug` in their metadata: {}",
+                duplicates.join("; ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Author pages are named after the slug of the id, so ids must not share a slug.
+    fn check_author_slugs(authors: &[Author]) -> Result<()> {
+        let duplicates = authors
+            .iter()
+            .into_group_map_by(|author| author.page_path())
+            .into_iter()
+            .filter(|(_, authors)| authors.len() > 1)
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(path, authors)| {
+                let ids = authors
+                    .iter()
+                    .map(|author| format!("{:?}", author.id()))
+                    .join(", ");
+                format!("{path:?} is used by {ids}")
+            })
+            .collect::<Vec<_>>();
+        if !duplicates.is_empty() {
+            bail!(
+                "Authors must have ids with unique slugs, rename them in {CONFIG_FILENAME}: {}",
+                duplicates.join("; ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Posts must refer to authors configured in an `[authors.<id>]` table.
+    fn check_authors(posts: &[Post], authors: &[Author]) -> Result<()> {
+        let unknown = posts
+            .iter()
+            .flat_map(|post| {
+                post.metadata()
+                    .authors
+                    .iter()
+                    .filter(|id| !authors.iter().any(|author| author.id() == id.as_str()))
+                    .map(move |id| format!("{id:?} in {:?}", post.path()))
+            })
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            bail!(
+                "Unknown authors, add them as `[authors.<id>]` to {CONFIG_FILENAME}: {}",
+                unknown.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    fn is_markdown_file(path: &Path) -> bool {
+        path.extension()
+            .map(|extension| EXT_POSTS.iter().any(|ext| extension == *ext))
+            .unwrap_or_default()
+    }
+
     /// Get union of requested and available theme features
     pub fn resolve_features(&self) -> Vec<String> {
         let features_available = self.theme.features();
@@ -62,16 +352,61 @@ impl Blog {
     pub fn iter_posts(&self, env: BuildEnvironment) -> Box<dyn Iterator<Item = &Post> + '_> {
         match env {
             BuildEnvironment::Development => Box::new(self.posts.iter()),
-            BuildEnvironment::Production => {
-                Box::new(self.posts.iter().filter(|post| post.metadata().published))
-            }
+            BuildEnvironment::Production => Box::new(
+                self.posts
+                    .iter()
+                    .filter(|post| post.metadata().is_live_at(self.build_time)),
+            ),
         }
     }
 
+    /// Get all paths whose contents influence the build output.
+    pub fn source_paths(&self) -> Vec<PathBuf> {
+        let theme_path = self.theme.path();
+        vec![
+            PathBuf::from(CONFIG_FILENAME),
+            PathBuf::from("posts"),
+            PathBuf::from("pages"),
+            PathBuf::from("static"),
+            theme_path.join(CONFIG_FILENAME),
+            theme_path.join("layouts"),
+            theme_path.join("styles"),
+            theme_path.join("scripts"),
+            theme_path.join("assets"),
+        ]
+    }
+
+    /// Get the standalone pages of the `pages/` directory.
+    pub fn pages(&self) -> &[Page] {
+        &self.pages
+    }
+
+    /// Get the configured authors, ordered by id.
+    pub fn authors(&self) -> &[Author] {
+        &self.authors
+    }
+
+    /// Get the authors of a post, in the order of its metadata.
+    pub fn post_authors<'a>(&'a self, post: &'a Post) -> impl Iterator<Item = &'a Author> {
+        post.metadata()
+            .authors
+            .iter()
+            .filter_map(|id| self.authors.iter().find(|author| author.id() == id))
+    }
+
+    /// Get the files of the `static/` directory.
+    pub fn static_files(&self) -> &[StaticFile] {
+        &self.static_files
+    }
+
     pub fn config(&self) -> &BlogConfig {
         &self.config
     }
 
+    pub fn markdown_renderer(&self) -> &MarkdownRenderer {
+        &self.markdown_renderer
+    }
+
     pub fn theme(&self) -> &Theme {
         &self.theme
     }
@@ -88,6 +423,8 @@ impl Blog {
 
         create_dir_all(path)?;
         create_dir_all(path.join("posts"))?;
+        create_dir_all(path.join("pages"))?;
+        create_dir_all(path.join("static"))?;
 
         Ok(())
     }
@@ -139,6 +476,7 @@ impl Blog {
             path: path.to_path_buf(),
             blog_config: Some(blog_config),
             theme_config: Some(theme_config),
+            ..Default::default()
         };
 
         // Serialize config
@@ -174,3 +512,66 @@ impl Blog {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs::{create_dir_all, write},
+        path::Path,
+    };
+
+    use tempfile::tempdir;
+
+    use crate::blog::{
+        config::{AuthorConfig, BlogConfig},
+        Author, Permalink, Post,
+    };
+
+    use super::Blog;
+
+    #[test]
+    fn blog_load_post_bundles() {
+        let posts_dir = tempdir().unwrap();
+        let dir = posts_dir.path();
+        create_dir_all(dir.join("trip/day-one/img")).unwrap();
+        let post = |title: &str| format!("+++\ntitle = '{title}'\ncreated_at = 2023-01-01\n+++\n");
+        write(dir.join("index.md"), post("Index")).unwrap();
+        write(dir.join("trip/index.md"), post("Trip")).unwrap();
+        write(dir.join("trip/map.png"), "").unwrap();
+        write(dir.join("trip/day-one/index.md"), post("Day one")).unwrap();
+        write(dir.join("trip/day-one/img/beach.jpg"), "").unwrap();
+
+        let posts = Blog::load_posts(dir, &Permalink::default(), &BlogConfig::default()).unwrap();
+        let assets = |title: &str| {
+            let post = posts
+                .iter()
+                .find(|post| post.metadata().title == title)
+                .unwrap();
+            post.assets()
+                .iter()
+                .map(|asset| asset.virtual_path().to_path_buf())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(posts.len(), 3);
+        assert!(assets("Index").is_empty());
+        assert_eq!(assets("Trip"), vec![Path::new("map.png")]);
+        assert_eq!(assets("Day one"), vec![Path::new("img/beach.jpg")]);
+    }
+
+    #[test]
+    fn blog_check_empty_slugs() {
+        let post = |title: &str| {
+            Post::from_markdown_file_without_path(format!("+++\ntitle = '{title}'\n+++\n")).unwrap()
+        };
+        assert!(Blog::check_empty_slugs(&[post("Hello world"), post("C++?")]).is_ok());
+        assert!(Blog::check_empty_slugs(&[post("Hello world"), post("?!")]).is_err());
+        assert!(Blog::check_empty_slugs(&[post("* * *")]).is_err());
+    }
+
+    #[test]
+    fn blog_check_author_slugs() {
+        let author = |id: &str| Author::new(id, AuthorConfig::default());
+        assert!(Blog::check_author_slugs(&[author("jane"), author("john")]).is_ok());
+        assert!(Blog::check_author_slugs(&[author("jane.doe"), author("jane_doe")]).is_err());
+    }
+}
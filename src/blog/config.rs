@@ -1,7 +1,23 @@
+mod author_config;
 mod blog_config;
+mod blog_feed_config;
+mod blog_highlight_config;
+mod blog_history_config;
+mod blog_image_config;
 mod blog_metadata_config;
+mod blog_redirect_config;
+mod blog_robots_config;
+mod blog_sitemap_config;
 mod blog_theme_config;
 
-pub use blog_config::BlogConfig;
+pub use author_config::AuthorConfig;
+pub use blog_config::{BlogConfig, CreatedAtFallback, SlugSeparator};
+pub use blog_feed_config::{BlogFeedConfig, FeedContent, FeedFormat};
+pub use blog_highlight_config::{BlogHighlightConfig, HighlightStyle};
+pub use blog_history_config::BlogHistoryConfig;
+pub use blog_image_config::BlogImageConfig;
 pub use blog_metadata_config::BlogMetadataConfig;
+pub use blog_redirect_config::BlogRedirectConfig;
+pub use blog_robots_config::BlogRobotsConfig;
+pub use blog_sitemap_config::BlogSitemapConfig;
 pub use blog_theme_config::BlogThemeConfig;
@@ -0,0 +1,16 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the profile of an author, set in an `[authors.<id>]` table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthorConfig {
+    /// Name shown on the blog, defaults to the id of the author
+    pub name: String,
+    pub bio: Option<String>,
+    /// Absolute url or path below the base url of a profile picture
+    pub avatar: Option<String>,
+    /// Urls of profiles and websites of the author, keyed by their label
+    pub links: BTreeMap<String, String>,
+}
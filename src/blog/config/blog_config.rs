@@ -1,32 +1,101 @@
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
-use crate::engine::BuildEnvironment;
+use crate::{blog::permalink::DEFAULT_PERMALINK, engine::BuildEnvironment};
 
-use super::{BlogMetadataConfig, BlogThemeConfig};
+use super::{
+    BlogFeedConfig, BlogHighlightConfig, BlogHistoryConfig, BlogImageConfig, BlogMetadataConfig,
+    BlogRedirectConfig, BlogRobotsConfig, BlogSitemapConfig, BlogThemeConfig,
+};
 
 /// Configuration for a blog project.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlogConfig {
     pub name: String,
     pub base_url: Option<String>,
+    /// Number of posts per index page, all posts are shown on one page if unset
+    #[serde(default)]
+    pub posts_per_page: Option<usize>,
+    /// Pattern of post urls, a trailing `/` writes posts to `index.html` in that directory
+    #[serde(default = "default_permalink")]
+    pub permalink: String,
+    /// Separator of the words in post slugs generated from titles
+    #[serde(default)]
+    pub slug_separator: SlugSeparator,
+    /// Timezone of post datetimes without an offset, and of the dates shown on the blog
+    #[serde(default)]
+    pub timezone: Tz,
+    /// Source of the creation time of posts without `created_at`
+    #[serde(default)]
+    pub created_at_fallback: CreatedAtFallback,
     #[serde(rename = "theme")]
     pub theme_config: BlogThemeConfig,
     #[serde(rename = "metadata")]
     pub meta: BlogMetadataConfig,
+    #[serde(default)]
+    pub feeds: BlogFeedConfig,
+    #[serde(default)]
+    pub highlighting: BlogHighlightConfig,
+    #[serde(default)]
+    pub images: BlogImageConfig,
+    #[serde(default)]
+    pub history: BlogHistoryConfig,
+    #[serde(default)]
+    pub redirects: BlogRedirectConfig,
+    #[serde(default)]
+    pub sitemap: BlogSitemapConfig,
+    #[serde(default)]
+    pub robots: BlogRobotsConfig,
 }
 
 impl BlogConfig {
     pub fn base_url(&self, env: BuildEnvironment) -> String {
-        let local_base_url = std::env::current_dir()
-            .map(|path| path.join("build").to_string_lossy().to_string())
-            .unwrap_or_else(|_| "./build".to_string());
         match env {
-            BuildEnvironment::Development => local_base_url,
+            // The development server serves the build directory at its root
+            BuildEnvironment::Development => String::new(),
             BuildEnvironment::Production => {
                 self.base_url.clone().unwrap_or_else(|| "/".to_string())
             }
         }
     }
+
+    /// Get the number of index pages needed to list the given number of posts.
+    pub fn index_page_count(&self, post_count: usize) -> usize {
+        match self.posts_per_page {
+            Some(posts_per_page) if posts_per_page > 0 => {
+                post_count.div_ceil(posts_per_page).max(1)
+            }
+            _ => 1,
+        }
+    }
+
+    /// Turn a path relative to the blog root into an absolute production url.
+    pub fn absolute_url(&self, relative_url: impl AsRef<str>) -> String {
+        format!(
+            "{}/{}",
+            self.base_url(BuildEnvironment::Production)
+                .trim_end_matches('/'),
+            relative_url.as_ref().trim_start_matches('/')
+        )
+    }
+
+    /// Get the path the blog is served at in production, without a trailing `/`.
+    pub fn base_path(&self) -> String {
+        let base_url = self.base_url(BuildEnvironment::Production);
+        let path = match base_url.split_once("://") {
+            Some((_, rest)) => rest.find('/').map_or("", |index| &rest[index..]),
+            None => &base_url,
+        };
+        path.trim_end_matches('/').to_string()
+    }
+
+    /// Whether a base url with scheme and host has been configured.
+    pub fn has_absolute_base_url(&self) -> bool {
+        self.base_url
+            .as_ref()
+            .map(|base_url| base_url.starts_with("http://") || base_url.starts_with("https://"))
+            .unwrap_or_default()
+    }
 }
 
 impl Default for BlogConfig {
@@ -34,8 +103,58 @@ impl Default for BlogConfig {
         Self {
             name: "My blog".into(),
             base_url: None,
+            posts_per_page: None,
+            permalink: default_permalink(),
+            slug_separator: Default::default(),
+            timezone: Default::default(),
+            created_at_fallback: Default::default(),
             meta: Default::default(),
             theme_config: Default::default(),
+            feeds: Default::default(),
+            highlighting: Default::default(),
+            images: Default::default(),
+            history: Default::default(),
+            redirects: Default::default(),
+            sitemap: Default::default(),
+            robots: Default::default(),
         }
     }
 }
+
+/// Separator of the words in slugs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlugSeparator {
+    #[default]
+    #[serde(rename = "_")]
+    Underscore,
+    #[serde(rename = "-")]
+    Hyphen,
+}
+
+impl SlugSeparator {
+    pub fn as_char(&self) -> char {
+        match self {
+            SlugSeparator::Underscore => '_',
+            SlugSeparator::Hyphen => '-',
+        }
+    }
+}
+
+/// Source of the creation time of posts that don't set `created_at`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CreatedAtFallback {
+    /// Modification time of the markdown file
+    #[default]
+    #[serde(rename = "mtime")]
+    ModificationTime,
+    /// Author date of the first commit of the markdown file
+    #[serde(rename = "git")]
+    Git,
+    /// Fail the build
+    #[serde(rename = "error")]
+    Error,
+}
+
+fn default_permalink() -> String {
+    DEFAULT_PERMALINK.to_string()
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for RSS, Atom and JSON feeds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlogFeedConfig {
+    /// Feed formats to generate
+    pub formats: Vec<FeedFormat>,
+    /// Maximum number of posts per feed
+    pub limit: usize,
+    /// Whether entries contain the full post or only a summary
+    pub content: FeedContent,
+}
+
+impl Default for BlogFeedConfig {
+    fn default() -> Self {
+        Self {
+            formats: vec![FeedFormat::Rss, FeedFormat::Atom, FeedFormat::Json],
+            limit: 20,
+            content: FeedContent::Full,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            Self::Rss => "rss.xml",
+            Self::Atom => "atom.xml",
+            Self::Json => "feed.json",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Rss => "application/rss+xml",
+            Self::Atom => "application/atom+xml",
+            Self::Json => "application/feed+json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedContent {
+    Full,
+    Summary,
+}
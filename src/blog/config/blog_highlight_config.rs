@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for syntax highlighting of fenced code blocks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlogHighlightConfig {
+    pub enabled: bool,
+    /// Name of the color scheme, e.g. `base16-ocean.dark` or `InspiredGitHub`
+    pub theme: String,
+    /// Whether code is colored by inline styles or by classes from `highlight.css`
+    pub style: HighlightStyle,
+}
+
+impl Default for BlogHighlightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            theme: "base16-ocean.dark".into(),
+            style: HighlightStyle::Inline,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightStyle {
+    Inline,
+    Classes,
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the revision history of posts read from git.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlogHistoryConfig {
+    /// Use the date of the last commit of posts changed since their first commit as `updated_at`
+    pub git_updated_at: bool,
+    /// Maximum number of commits listed as revisions of a post, none if 0
+    pub revisions: usize,
+}
+
+impl BlogHistoryConfig {
+    /// Whether the git history of posts has to be read.
+    pub fn uses_git(&self) -> bool {
+        self.git_updated_at || self.revisions > 0
+    }
+}
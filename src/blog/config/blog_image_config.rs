@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for resized variants of images referenced by posts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlogImageConfig {
+    pub enabled: bool,
+    /// Widths of the generated variants in pixels, images are never scaled up
+    pub widths: Vec<u32>,
+    /// Value of the `sizes` attribute of rewritten images
+    pub sizes: String,
+    /// Whether to generate WebP variants in addition to the original format
+    pub webp: bool,
+    /// Encoding quality of JPEG and WebP variants, from 1 to 100
+    pub quality: u8,
+}
+
+impl Default for BlogImageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            widths: vec![480, 960, 1920],
+            sizes: "(max-width: 960px) 100vw, 960px".into(),
+            webp: false,
+            quality: 80,
+        }
+    }
+}
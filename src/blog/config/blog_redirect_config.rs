@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for redirects from the aliases of posts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlogRedirectConfig {
+    /// Write a `_redirects` file for Netlify and Cloudflare Pages
+    pub netlify: bool,
+    /// Write an nginx map of the redirects to `redirects.nginx.conf`
+    pub nginx: bool,
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for `robots.txt`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlogRobotsConfig {
+    pub enabled: bool,
+    /// Crawler the rules apply to
+    pub user_agent: String,
+    /// Paths crawlers may visit
+    pub allow: Vec<String>,
+    /// Paths crawlers should not visit
+    pub disallow: Vec<String>,
+}
+
+impl Default for BlogRobotsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            user_agent: "*".into(),
+            allow: Vec::default(),
+            disallow: Vec::default(),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Configuration for `sitemap.xml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlogSitemapConfig {
+    pub enabled: bool,
+}
+
+impl Default for BlogSitemapConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
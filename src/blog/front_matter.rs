@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+static RE_COMMENT: Lazy<Regex> = Lazy::new(|| {
+    let re = r"(?im)^\[[/]{2}\]: # \((?P<key>.*?):\s+(?P<value>.*?)\)$";
+    Regex::new(re).unwrap()
+});
+
+/// Metadata values by key, as read from the front matter of a post.
+pub type FrontMatterValues = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontMatterFormat {
+    /// `+++` delimited TOML block at the start of the file
+    Toml,
+    /// `---` delimited YAML block at the start of the file
+    Yaml,
+    /// `[//]: # (key: value)` comments anywhere in the file
+    Comments,
+}
+
+impl FrontMatterFormat {
+    fn delimiter(&self) -> Option<&'static str> {
+        match self {
+            FrontMatterFormat::Toml => Some("+++"),
+            FrontMatterFormat::Yaml => Some("---"),
+            FrontMatterFormat::Comments => None,
+        }
+    }
+}
+
+/// Front matter of a markdown source, split from its content.
+#[derive(Debug)]
+pub struct FrontMatter<'a> {
+    format: FrontMatterFormat,
+    raw: &'a str,
+    content: &'a str,
+}
+
+impl<'a> FrontMatter<'a> {
+    /// Split a markdown source into its front matter and content.
+    ///
+    /// Sources without a delimited front matter block fall back to metadata comments,
+    /// which are left in the content since they don't render to anything.
+    pub fn split(source: &'a str) -> Self {
+        for format in [FrontMatterFormat::Toml, FrontMatterFormat::Yaml] {
+            if let Some((raw, content)) = Self::split_delimited(source, format) {
+                return Self {
+                    format,
+                    raw,
+                    content,
+                };
+            }
+        }
+        Self {
+            format: FrontMatterFormat::Comments,
+            raw: source,
+            content: source,
+        }
+    }
+
+    fn split_delimited(source: &'a str, format: FrontMatterFormat) -> Option<(&'a str, &'a str)> {
+        let delimiter = format.delimiter()?;
+        let (first_line, rest) = source.split_once('\n')?;
+        if first_line.trim_end() != delimiter {
+            return None;
+        }
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end() == delimiter {
+                let raw = &rest[..offset];
+                let content = &rest[offset + line.len()..];
+                return Some((raw, content));
+            }
+            offset += line.len();
+        }
+        None
+    }
+
+    #[cfg(test)]
+    pub fn format(&self) -> FrontMatterFormat {
+        self.format
+    }
+
+    /// Get the markdown content following the front matter.
+    pub fn content(&self) -> &'a str {
+        self.content
+    }
+
+    /// Parse the front matter into its values.
+    pub fn parse(&self) -> Result<FrontMatterValues> {
+        match self.format {
+            FrontMatterFormat::Toml => {
+                let value: toml::Value =
+                    toml::from_str(self.raw).context("Invalid TOML front matter")?;
+                match toml_to_json(value) {
+                    Value::Object(map) => Ok(map.into_iter().collect()),
+                    _ => bail!("TOML front matter must be a table"),
+                }
+            }
+            FrontMatterFormat::Yaml => {
+                let value: Value =
+                    serde_yaml::from_str(self.raw).context("Invalid YAML front matter")?;
+                match value {
+                    Value::Object(map) => Ok(map.into_iter().collect()),
+                    Value::Null => Ok(FrontMatterValues::new()),
+                    _ => bail!("YAML front matter must be a mapping"),
+                }
+            }
+            FrontMatterFormat::Comments => Ok(self
+                .raw
+                .lines()
+                .filter_map(|line| RE_COMMENT.captures(line))
+                .fold(FrontMatterValues::new(), |mut accum, captures| {
+                    accum
+                        .entry(captures["key"].to_string())
+                        .or_insert_with(|| Value::String(captures["value"].to_string()));
+                    accum
+                })),
+        }
+    }
+}
+
+/// Convert a TOML value to JSON, turning datetimes into their string representation.
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(value) => Value::String(value),
+        toml::Value::Integer(value) => Value::from(value),
+        toml::Value::Float(value) => Value::from(value),
+        toml::Value::Boolean(value) => Value::Bool(value),
+        toml::Value::Datetime(value) => Value::String(value.to_string()),
+        toml::Value::Array(values) => Value::Array(values.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{FrontMatter, FrontMatterFormat};
+
+    #[test]
+    fn front_matter_split() {
+        let toml = FrontMatter::split("+++\ntitle = \"Hello\"\n+++\n# Hello\n");
+        assert_eq!(toml.format(), FrontMatterFormat::Toml);
+        assert_eq!(toml.content(), "# Hello\n");
+
+        let yaml = FrontMatter::split("---\ntitle: Hello\n---\n# Hello");
+        assert_eq!(yaml.format(), FrontMatterFormat::Yaml);
+        assert_eq!(yaml.content(), "# Hello");
+
+        let unterminated = FrontMatter::split("---\n# Hello");
+        assert_eq!(unterminated.format(), FrontMatterFormat::Comments);
+        assert_eq!(unterminated.content(), "---\n# Hello");
+    }
+
+    #[test]
+    fn front_matter_parse_typed_values() {
+        let toml = FrontMatter::split(
+            "+++\ndate = 2023-01-02T03:04:05\ntags = [\"a\", \"b\"]\n[series]\nname = \"x\"\n+++\n",
+        );
+        let values = toml.parse().unwrap();
+        assert_eq!(values["date"], json!("2023-01-02T03:04:05"));
+        assert_eq!(values["tags"], json!(["a", "b"]));
+        assert_eq!(values["series"], json!({ "name": "x" }));
+
+        let yaml = FrontMatter::split("---\ntags:\n  - a\n  - b\npublished: true\n---\n");
+        let values = yaml.parse().unwrap();
+        assert_eq!(values["tags"], json!(["a", "b"]));
+        assert_eq!(values["published"], json!(true));
+    }
+}
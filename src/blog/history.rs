@@ -0,0 +1,89 @@
+use std::{fs::metadata, path::Path, process::Command};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+
+/// A commit that changed a file.
+#[derive(Debug, Clone, Hash)]
+pub struct Revision {
+    /// Author date of the commit
+    pub date: DateTime<Utc>,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+/// Get the time a file was last modified.
+pub fn modified_at(path: &Path) -> Result<DateTime<Utc>> {
+    let modified = metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .with_context(|| format!("Unable to read the modification time of {path:?}"))?;
+    Ok(modified.into())
+}
+
+/// Get the author date of the first commit of a file in the local git repository.
+/// Files that have not been committed yet have none.
+pub fn first_commit_at(path: &Path) -> Result<Option<DateTime<Utc>>> {
+    Ok(revisions(path)?.last().map(|revision| revision.date))
+}
+
+/// Get the author date of the first commit of a file, or the time it was last modified
+/// if it has not been committed yet or its history can't be read, e.g. without git
+/// or outside of a repository.
+pub fn first_commit_or_modified_at(path: &Path) -> Result<DateTime<Utc>> {
+    match first_commit_at(path) {
+        Ok(Some(created_at)) => return Ok(created_at),
+        Ok(None) => log::debug!("{path:?} has not been committed yet"),
+        Err(error) => log::warn!("Using the modification time of {path:?}: {error:#}"),
+    }
+    modified_at(path)
+}
+
+/// List the commits of a file in the local git repository, newest first, following renames.
+pub fn revisions(path: &Path) -> Result<Vec<Revision>> {
+    let output = Command::new("git")
+        .args(["log", "--follow", "--format=%aI%x00%s", "--"])
+        .arg(path)
+        .output()
+        .context("Unable to run git")?;
+    if !output.status.success() {
+        bail!(
+            "Unable to read the git history of {path:?}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (date, summary) = line.split_once('\0').unwrap_or((line, ""));
+            let date = DateTime::parse_from_rfc3339(date)
+                .with_context(|| format!("Unexpected commit date {date:?}"))?;
+            Ok(Revision {
+                date: date.with_timezone(&Utc),
+                summary: summary.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use tempfile::tempdir;
+
+    use super::{first_commit_at, first_commit_or_modified_at, modified_at};
+
+    #[test]
+    fn history_outside_of_repository() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("post.md");
+        write(&path, "# Hello world").unwrap();
+
+        assert!(first_commit_at(&path).is_err());
+        assert_eq!(
+            first_commit_or_modified_at(&path).unwrap(),
+            modified_at(&path).unwrap()
+        );
+    }
+}
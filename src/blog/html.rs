@@ -0,0 +1,20 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+/// Build an opening html tag with the given attributes in a stable order.
+pub fn opening_tag(name: &str, attributes: &HashMap<String, String>) -> String {
+    let attributes = attributes
+        .iter()
+        .sorted()
+        .map(|(key, value)| format!(" {key}=\"{}\"", escape(value)))
+        .join("");
+    format!("<{name}{attributes}>")
+}
+
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use comrak::{
+    nodes::{AstNode, NodeCode, NodeValue},
+    Anchorizer, Arena, ComrakExtensionOptions, ComrakOptions, ComrakPlugins, ComrakRenderOptions,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{config::BlogConfig, html::opening_tag, syntax_highlighter::SyntaxHighlighter};
+
+static RE_URL_SCHEME: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*:").unwrap());
+
+/// Html rendered from markdown, along with the headings it contains.
+#[derive(Debug)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    /// Headings in document order
+    pub headings: Vec<Heading>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    /// Id of the anchor generated for the heading
+    pub id: String,
+}
+
+/// Responsive variants of images, keyed by the url of the image in the markdown.
+pub type ResponsiveImages = HashMap<String, ResponsiveImage>;
+
+/// Resized variants of an image, rendered as a lazily loaded `<img>` with a srcset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponsiveImage {
+    /// Url of the variant used by browsers without srcset support
+    pub src: String,
+    pub srcset: String,
+    /// Srcset of the WebP variants, offered through a `<picture>` element if present
+    pub webp_srcset: Option<String>,
+    pub sizes: String,
+    /// Dimensions of the original image, reserving space while it loads
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ResponsiveImage {
+    fn to_html(&self, alt: &str, title: &str) -> String {
+        let mut attributes = HashMap::from([
+            ("src".to_string(), self.src.clone()),
+            ("srcset".to_string(), self.srcset.clone()),
+            ("sizes".to_string(), self.sizes.clone()),
+            ("width".to_string(), self.width.to_string()),
+            ("height".to_string(), self.height.to_string()),
+            ("alt".to_string(), alt.to_string()),
+            ("loading".to_string(), "lazy".to_string()),
+            ("decoding".to_string(), "async".to_string()),
+        ]);
+        if !title.is_empty() {
+            attributes.insert("title".into(), title.into());
+        }
+        let img = opening_tag("img", &attributes);
+        match &self.webp_srcset {
+            Some(webp_srcset) => {
+                let source = opening_tag(
+                    "source",
+                    &HashMap::from([
+                        ("type".to_string(), "image/webp".to_string()),
+                        ("srcset".to_string(), webp_srcset.clone()),
+                        ("sizes".to_string(), self.sizes.clone()),
+                    ]),
+                );
+                format!("<picture>{source}{img}</picture>")
+            }
+            None => img,
+        }
+    }
+}
+
+/// Renders the markdown of posts to html.
+#[derive(Debug, Default)]
+pub struct MarkdownRenderer {
+    highlighter: Option<SyntaxHighlighter>,
+}
+
+impl MarkdownRenderer {
+    pub fn new(config: &BlogConfig) -> Result<Self> {
+        let highlighter = if config.highlighting.enabled {
+            Some(SyntaxHighlighter::new(&config.highlighting)?)
+        } else {
+            None
+        };
+        Ok(Self { highlighter })
+    }
+
+    /// Get the stylesheet for highlighted code, if the highlighting style needs one.
+    pub fn highlight_stylesheet(&self) -> Result<Option<String>> {
+        match &self.highlighter {
+            Some(highlighter) => highlighter.stylesheet(),
+            None => Ok(None),
+        }
+    }
+
+    /// Render markdown to html, resolving relative links against `link_base` if given.
+    pub fn render(
+        &self,
+        markdown: &str,
+        link_base: Option<&str>,
+        images: Option<&ResponsiveImages>,
+    ) -> Result<String> {
+        Ok(self.render_document(markdown, link_base, images)?.html)
+    }
+
+    /// Render markdown to html and collect its headings.
+    ///
+    /// Images with responsive variants in `images` are rendered with a srcset,
+    /// relative urls of other links and images are resolved against `link_base` if given.
+    pub fn render_document(
+        &self,
+        markdown: &str,
+        link_base: Option<&str>,
+        images: Option<&ResponsiveImages>,
+    ) -> Result<RenderedMarkdown> {
+        let options = Self::options();
+        let mut plugins = ComrakPlugins::default();
+        plugins.render.codefence_syntax_highlighter = self
+            .highlighter
+            .as_ref()
+            .map(|highlighter| highlighter as _);
+
+        let arena = Arena::new();
+        let root = comrak::parse_document(&arena, markdown, &options);
+
+        if let Some(images) = images {
+            let image_nodes = root
+                .descendants()
+                .filter(|node| matches!(node.data.borrow().value, NodeValue::Image(_)))
+                .collect::<Vec<_>>();
+            for node in image_nodes {
+                let html = match node.data.borrow().value {
+                    NodeValue::Image(ref link) => {
+                        let Some(image) = images.get(String::from_utf8_lossy(&link.url).as_ref())
+                        else {
+                            continue;
+                        };
+                        let mut alt = Vec::new();
+                        collect_text(node, &mut alt);
+                        image.to_html(
+                            &String::from_utf8_lossy(&alt),
+                            &String::from_utf8_lossy(&link.title),
+                        )
+                    }
+                    _ => continue,
+                };
+                for child in node.children().collect::<Vec<_>>() {
+                    child.detach();
+                }
+                node.data.borrow_mut().value = NodeValue::HtmlInline(html.into_bytes());
+            }
+        }
+
+        if let Some(link_base) = link_base {
+            for node in root.descendants() {
+                if let NodeValue::Link(ref mut link) | NodeValue::Image(ref mut link) =
+                    node.data.borrow_mut().value
+                {
+                    link.url = resolve_url(&link.url, link_base);
+                }
+            }
+        }
+
+        // Anchor ids are generated the same way comrak does while rendering
+        let mut anchorizer = Anchorizer::new();
+        let headings = root
+            .descendants()
+            .filter_map(|node| match node.data.borrow().value {
+                NodeValue::Heading(heading) => Some((node, heading.level)),
+                _ => None,
+            })
+            .map(|(node, level)| {
+                let mut text = Vec::new();
+                collect_text(node, &mut text);
+                let text = String::from_utf8_lossy(&text).to_string();
+                Heading {
+                    level: level as u8,
+                    id: anchorizer.anchorize(text.clone()),
+                    text,
+                }
+            })
+            .collect();
+
+        let mut html = Vec::new();
+        comrak::format_html_with_plugins(root, &options, &mut html, &plugins)?;
+        let html = String::from_utf8(html)?.trim().to_string();
+        Ok(RenderedMarkdown { html, headings })
+    }
+
+    /// Get the urls of all images in the markdown, in document order.
+    pub fn image_urls(markdown: &str) -> Vec<String> {
+        let arena = Arena::new();
+        let root = comrak::parse_document(&arena, markdown, &Self::options());
+        root.descendants()
+            .filter_map(|node| match node.data.borrow().value {
+                NodeValue::Image(ref link) => Some(String::from_utf8_lossy(&link.url).to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn options() -> ComrakOptions {
+        // Allow unsafe HTML code in posts
+        let render = ComrakRenderOptions {
+            unsafe_: true,
+            ..Default::default()
+        };
+        let extension = ComrakExtensionOptions {
+            table: true,
+            autolink: true,
+            footnotes: true,
+            header_ids: Some(String::default()),
+            ..Default::default()
+        };
+        ComrakOptions {
+            render,
+            extension,
+            ..Default::default()
+        }
+    }
+}
+
+/// Whether the url is relative to the document, as opposed to absolute urls and fragments.
+pub fn is_relative_url(url: &str) -> bool {
+    !(url.is_empty() || url.starts_with(['/', '#', '?']) || RE_URL_SCHEME.is_match(url))
+}
+
+/// Prefix relative urls with the base, leaving absolute urls and fragments alone.
+fn resolve_url(url: &[u8], base: &str) -> Vec<u8> {
+    let url = String::from_utf8_lossy(url);
+    if !is_relative_url(&url) {
+        return url.as_bytes().to_vec();
+    }
+    let url = url.trim_start_matches("./");
+    format!("{}/{url}", base.trim_end_matches('/')).into_bytes()
+}
+
+fn collect_text<'a>(node: &'a AstNode<'a>, output: &mut Vec<u8>) {
+    match node.data.borrow().value {
+        NodeValue::Text(ref literal) | NodeValue::Code(NodeCode { ref literal, .. }) => {
+            output.extend_from_slice(literal)
+        }
+        NodeValue::LineBreak | NodeValue::SoftBreak => output.push(b' '),
+        _ => {
+            for child in node.children() {
+                collect_text(child, output);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::blog::config::{BlogConfig, HighlightStyle};
+
+    use super::{Heading, MarkdownRenderer, ResponsiveImage, ResponsiveImages};
+
+    #[test]
+    fn markdown_renderer_highlighting() {
+        let mut config = BlogConfig::default();
+        let markdown = "```rust\nfn main() {}\n```";
+
+        let html = MarkdownRenderer::new(&config)
+            .unwrap()
+            .render(markdown, None, None)
+            .unwrap();
+        assert!(html
+            .starts_with(r#"<pre style="background-color:#2b303b;"><code class="language-rust">"#));
+        assert!(html.contains(r#"<span style="color:#b48ead;">fn </span>"#));
+
+        config.highlighting.style = HighlightStyle::Classes;
+        let renderer = MarkdownRenderer::new(&config).unwrap();
+        let html = renderer.render(markdown, None, None).unwrap();
+        assert!(html.starts_with(r#"<pre class="hl-code"><code class="language-rust">"#));
+        assert!(html.contains(r#"<span class="hl-storage hl-type hl-function hl-rust">fn</span>"#));
+        assert!(renderer.highlight_stylesheet().unwrap().is_some());
+
+        config.highlighting.theme = "unknown".into();
+        assert!(MarkdownRenderer::new(&config).is_err());
+    }
+
+    #[test]
+    fn markdown_renderer_headings() {
+        let markdown = "# Hello `world`\n## Section\ntext\n## Section";
+        let document = MarkdownRenderer::default()
+            .render_document(markdown, None, None)
+            .unwrap();
+        let heading = |level, text: &str, id: &str| Heading {
+            level,
+            text: text.into(),
+            id: id.into(),
+        };
+        assert_eq!(
+            document.headings,
+            vec![
+                heading(1, "Hello world", "hello-world"),
+                heading(2, "Section", "section"),
+                heading(2, "Section", "section-1"),
+            ]
+        );
+        assert!(document.html.contains(r#"id="section-1""#));
+    }
+
+    #[test]
+    fn markdown_renderer_link_base() {
+        let markdown =
+            "![a](a.jpg) [b](./b.pdf) [c](/c) [d](#d) [e](https://e.com) [f](mailto:f@f.f)";
+        let html = MarkdownRenderer::default()
+            .render(markdown, Some("/posts/hello/"), None)
+            .unwrap();
+        assert_eq!(
+            html,
+            r##"<p><img src="/posts/hello/a.jpg" alt="a" /> <a href="/posts/hello/b.pdf">b</a> <a href="/c">c</a> <a href="#d">d</a> <a href="https://e.com">e</a> <a href="mailto:f@f.f">f</a></p>"##
+        );
+    }
+
+    #[test]
+    fn markdown_renderer_responsive_images() {
+        let markdown = "![A *beach*](img/beach.jpg \"Sunset\") ![b](b.jpg)";
+        let mut image = ResponsiveImage {
+            src: "/img/beach-960w.jpg".into(),
+            srcset: "/img/beach-480w.jpg 480w, /img/beach-960w.jpg 960w".into(),
+            webp_srcset: None,
+            sizes: "100vw".into(),
+            width: 1200,
+            height: 800,
+        };
+        let render = |image: &ResponsiveImage| {
+            let images = ResponsiveImages::from([("img/beach.jpg".to_string(), image.clone())]);
+            MarkdownRenderer::default()
+                .render(markdown, Some("/posts/hello/"), Some(&images))
+                .unwrap()
+        };
+        assert_eq!(
+            render(&image),
+            r#"<p><img alt="A beach" decoding="async" height="800" loading="lazy" sizes="100vw" src="/img/beach-960w.jpg" srcset="/img/beach-480w.jpg 480w, /img/beach-960w.jpg 960w" title="Sunset" width="1200"> <img src="/posts/hello/b.jpg" alt="b" /></p>"#
+        );
+
+        image.webp_srcset = Some("/img/beach-480w.webp 480w".into());
+        assert!(render(&image).starts_with(
+            r#"<p><picture><source sizes="100vw" srcset="/img/beach-480w.webp 480w" type="image/webp"><img alt="A beach""#
+        ));
+        assert_eq!(
+            MarkdownRenderer::image_urls(markdown),
+            vec!["img/beach.jpg", "b.jpg"]
+        );
+    }
+}
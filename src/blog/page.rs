@@ -0,0 +1,70 @@
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use super::{
+    front_matter::FrontMatter, post::Post, MarkdownRenderer, PageMetadata, RenderedMarkdown,
+};
+
+/// A standalone page loaded from the `pages/` directory, like an about page.
+///
+/// Pages are rendered to top-level urls and never listed along with posts.
+#[derive(Debug)]
+pub struct Page {
+    path: PathBuf,
+    virtual_path: PathBuf,
+    metadata: PageMetadata,
+    source: String,
+}
+
+impl Page {
+    /// Load a page from its markdown file and the directory pages are loaded from.
+    pub fn from_markdown_file(path: PathBuf, base_path: impl AsRef<Path>) -> Result<Self> {
+        let base_path = base_path.as_ref();
+        let virtual_path = path
+            .strip_prefix(base_path)
+            .with_context(|| format!("Page {path:?} is outside of {base_path:?}"))?
+            .with_extension("html");
+        let source = read_to_string(&path)?.trim().to_string();
+        let default_title = path.file_stem().unwrap_or_default().to_string_lossy();
+        let metadata = PageMetadata::from_markdown(&source, &default_title)
+            .with_context(|| format!("Invalid metadata in {path:?}"))?;
+        Ok(Self {
+            path,
+            virtual_path,
+            metadata,
+            source,
+        })
+    }
+
+    pub fn metadata(&self) -> &PageMetadata {
+        &self.metadata
+    }
+
+    /// Get the path of the markdown file the page was loaded from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Get the path the page is rendered to, relative to the build directory.
+    pub fn virtual_path(&self) -> &Path {
+        &self.virtual_path
+    }
+
+    /// Render the page to minified html and collect its headings
+    pub fn to_html_minified(&self, renderer: &MarkdownRenderer) -> Result<RenderedMarkdown> {
+        let content = FrontMatter::split(&self.source).content();
+        let rendered = renderer.render_document(content, None, None)?;
+        Ok(RenderedMarkdown {
+            html: Post::minify_html(&rendered.html)?,
+            ..rendered
+        })
+    }
+}
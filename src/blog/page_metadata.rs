@@ -0,0 +1,85 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use super::{
+    front_matter::{FrontMatter, FrontMatterValues},
+    post_metadata::{get_bool, get_string},
+};
+
+/// Metadata keys handled by crablog itself, everything else ends up in `extra`.
+const KNOWN_KEYS: &[&str] = &["title", "menu", "weight"];
+
+#[derive(Debug)]
+pub struct PageMetadata {
+    pub title: String,
+    /// Whether the page is linked in the menu
+    pub menu: bool,
+    /// Position of the page in the menu, lower weights come first
+    pub weight: i64,
+    /// Custom metadata for use in themes
+    pub extra: FrontMatterValues,
+}
+
+impl PageMetadata {
+    /// Parse the front matter of a page, using `default_title` if it has no title.
+    pub fn from_markdown(source: impl AsRef<str>, default_title: &str) -> Result<Self> {
+        let values = FrontMatter::split(source.as_ref()).parse()?;
+        let title = get_string(&values, "title")?.unwrap_or_else(|| default_title.to_string());
+        let menu = get_bool(&values, "menu")?.unwrap_or(true);
+        let weight = get_integer(&values, "weight")?.unwrap_or_default();
+        let extra = values
+            .iter()
+            .filter(|(key, _)| !KNOWN_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        Ok(Self {
+            title,
+            menu,
+            weight,
+            extra,
+        })
+    }
+}
+
+fn get_integer(values: &FrontMatterValues, key: &str) -> Result<Option<i64>> {
+    match values.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(value)) => match value.as_i64() {
+            Some(value) => Ok(Some(value)),
+            None => bail!("Expected `{key}` to be an integer, got {value}"),
+        },
+        Some(Value::String(value)) => match value.trim().parse() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => bail!("Expected `{key}` to be an integer, got {value:?}"),
+        },
+        Some(value) => bail!("Expected `{key}` to be an integer, got {value}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::PageMetadata;
+
+    #[test]
+    fn page_metadata_from_markdown() {
+        let metadata = PageMetadata::from_markdown("# About", "about").unwrap();
+        assert_eq!(metadata.title, "about");
+        assert!(metadata.menu);
+        assert_eq!(metadata.weight, 0);
+
+        let metadata = PageMetadata::from_markdown(
+            "+++\ntitle = \"Uses\"\nmenu = false\nweight = -1\nicon = \"wrench\"\n+++\n",
+            "uses",
+        )
+        .unwrap();
+        assert_eq!(metadata.title, "Uses");
+        assert!(!metadata.menu);
+        assert_eq!(metadata.weight, -1);
+        assert_eq!(metadata.extra["icon"], json!("wrench"));
+
+        assert!(PageMetadata::from_markdown("[//]: # (weight: first)", "about").is_err());
+    }
+}
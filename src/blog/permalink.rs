@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use chrono::Datelike;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use super::{config::SlugSeparator, slug::slugify, PostMetadata};
+
+static RE_PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{(\w*)\}").unwrap());
+
+/// Placeholders available in permalink patterns.
+const PLACEHOLDERS: &[&str] = &["year", "month", "day", "slug", "category"];
+
+/// Permalink pattern of posts without configuration.
+pub const DEFAULT_PERMALINK: &str = "posts/{slug}.html";
+
+/// Category used for the `{category}` placeholder of posts without categories.
+const DEFAULT_CATEGORY: &str = "uncategorized";
+
+/// Pattern that post urls are generated from, e.g. `{year}/{month}/{slug}/`.
+///
+/// Patterns ending with a `/` produce pretty urls, written to `index.html` in that directory.
+#[derive(Debug, Clone)]
+pub struct Permalink {
+    pattern: String,
+    /// Separator of the words in slugs
+    separator: char,
+}
+
+impl Permalink {
+    pub fn new(pattern: impl AsRef<str>, separator: SlugSeparator) -> Result<Self> {
+        let pattern = pattern.as_ref().trim().trim_start_matches('/');
+        for captures in RE_PLACEHOLDER.captures_iter(pattern) {
+            let placeholder = &captures[1];
+            if !PLACEHOLDERS.contains(&placeholder) {
+                bail!(
+                    "Unknown placeholder {{{placeholder}}} in permalink {pattern:?}, available placeholders: {}",
+                    PLACEHOLDERS.join(", ")
+                );
+            }
+        }
+        if !pattern.contains("{slug}") {
+            bail!("Permalink {pattern:?} must contain {{slug}} to give every post its own url");
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            separator: separator.as_char(),
+        })
+    }
+
+    /// Get the slug of a post, as used by the `{slug}` placeholder.
+    pub fn slug(&self, metadata: &PostMetadata) -> String {
+        metadata.slug(self.separator)
+    }
+
+    /// Get the url of a post relative to the base url of the blog.
+    pub fn relative_url(&self, metadata: &PostMetadata) -> String {
+        let created_at = metadata.created_at;
+        RE_PLACEHOLDER
+            .replace_all(&self.pattern, |captures: &Captures| match &captures[1] {
+                "year" => format!("{:04}", created_at.year()),
+                "month" => format!("{:02}", created_at.month()),
+                "day" => format!("{:02}", created_at.day()),
+                "slug" => self.slug(metadata),
+                "category" => metadata
+                    .categories
+                    .first()
+                    .map(|category| slugify(category, self.separator))
+                    .unwrap_or_else(|| DEFAULT_CATEGORY.to_string()),
+                _ => String::new(),
+            })
+            .to_string()
+    }
+}
+
+impl Default for Permalink {
+    fn default() -> Self {
+        Self {
+            pattern: DEFAULT_PERMALINK.to_string(),
+            separator: SlugSeparator::default().as_char(),
+        }
+    }
+}
+
+/// Get the path a page is written to for its url, relative to the build directory.
+pub fn url_to_virtual_path(relative_url: &str) -> PathBuf {
+    if relative_url.is_empty() || relative_url.ends_with('/') {
+        PathBuf::from(relative_url).join("index.html")
+    } else {
+        PathBuf::from(relative_url)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    use crate::blog::{config::SlugSeparator, PostMetadata};
+
+    use super::{url_to_virtual_path, Permalink};
+
+    #[test]
+    fn permalink_relative_url() {
+        let metadata = PostMetadata {
+            title: "My post".into(),
+            created_at: Tz::UTC.with_ymd_and_hms(2024, 3, 5, 12, 0, 0).unwrap(),
+            categories: vec!["Rust Lang".into()],
+            ..Default::default()
+        };
+        let url = |pattern| {
+            Permalink::new(pattern, SlugSeparator::Underscore)
+                .unwrap()
+                .relative_url(&metadata)
+        };
+        assert_eq!(url("posts/{slug}.html"), "posts/my_post.html");
+        assert_eq!(url("/{year}/{month}/{slug}/"), "2024/03/my_post/");
+        assert_eq!(
+            url("{category}/{day}/{slug}.html"),
+            "rust_lang/05/my_post.html"
+        );
+        assert_eq!(
+            Permalink::default().relative_url(&metadata),
+            "posts/my_post.html"
+        );
+
+        let hyphenated = Permalink::new("{slug}/", SlugSeparator::Hyphen).unwrap();
+        assert_eq!(hyphenated.relative_url(&metadata), "my-post/");
+
+        assert!(Permalink::new("{year}/{title}/", SlugSeparator::Underscore).is_err());
+        assert!(Permalink::new("{year}/index.html", SlugSeparator::Underscore).is_err());
+    }
+
+    #[test]
+    fn permalink_virtual_path() {
+        assert_eq!(
+            url_to_virtual_path("2024/03/my_post/"),
+            Path::new("2024/03/my_post/index.html")
+        );
+        assert_eq!(
+            url_to_virtual_path("posts/my_post.html"),
+            Path::new("posts/my_post.html")
+        );
+    }
+}
@@ -2,21 +2,40 @@ use std::{
     borrow::Cow,
     fs::{read_to_string, File},
     io::Write,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use anyhow::Result;
-use comrak::{ComrakExtensionOptions, ComrakOptions, ComrakRenderOptions};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use minify_html::minify;
+use once_cell::sync::Lazy;
+use regex::Regex;
 
-use crate::traits::TryFromFile;
+use super::{
+    config::BlogHistoryConfig, front_matter::FrontMatter, permalink::url_to_virtual_path,
+    MarkdownRenderer, Permalink, PostMetadata, RenderedMarkdown, ResponsiveImages, Revision,
+    StaticFile,
+};
+
+static RE_MORE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*<!--\s*more\s*-->\s*$").unwrap());
 
-use super::PostMetadata;
+/// File name of the markdown file of a post bundle
+pub const BUNDLE_INDEX: &str = "index.md";
 
 #[derive(Debug)]
 pub struct Post {
+    path: PathBuf,
     metadata: PostMetadata,
     source: String,
+    /// Files next to the `index.md` of a post bundle
+    assets: Vec<StaticFile>,
+    /// Slug of the post, either set explicitly or generated from the title
+    slug: String,
+    /// Url of the post relative to the base url, as generated by the permalink pattern
+    relative_url: String,
+    /// Commits of the post read from git, newest first
+    revisions: Vec<Revision>,
 }
 
 impl Post {
@@ -24,63 +43,223 @@ impl Post {
         &self.metadata
     }
 
+    /// Get the path of the markdown file the post was loaded from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the post is a directory with an `index.md` and its assets.
+    pub fn is_bundle(&self) -> bool {
+        self.path.file_name() == Some(BUNDLE_INDEX.as_ref())
+    }
+
+    /// Get the images and attachments of a post bundle.
+    pub fn assets(&self) -> &[StaticFile] {
+        &self.assets
+    }
+
+    /// Get the url of the post relative to the base url of the blog.
+    pub fn relative_url(&self) -> &str {
+        &self.relative_url
+    }
+
+    /// Get the path the post is rendered to, relative to the build directory.
+    pub fn virtual_path(&self) -> PathBuf {
+        url_to_virtual_path(&self.relative_url)
+    }
+
+    /// Get the directory the assets of the post are copied to, relative to the build directory.
+    ///
+    /// Posts with pretty urls keep their assets next to their `index.html`,
+    /// other posts in a directory named like their html file.
+    pub fn assets_virtual_dir(&self) -> PathBuf {
+        let virtual_path = self.virtual_path();
+        if self.relative_url.ends_with('/') {
+            virtual_path.parent().unwrap_or(Path::new("")).to_path_buf()
+        } else {
+            virtual_path.with_extension("")
+        }
+    }
+
+    /// Get the commits of the post listed on its page, newest first.
+    pub fn revisions(&self) -> &[Revision] {
+        &self.revisions
+    }
+
+    /// Attach the git history of the post, newest commit first.
+    ///
+    /// Posts changed since their first commit get the date of their last commit as
+    /// `updated_at` if enabled, unless they set it explicitly.
+    #[must_use]
+    pub fn with_history(
+        mut self,
+        mut revisions: Vec<Revision>,
+        config: &BlogHistoryConfig,
+    ) -> Self {
+        if config.git_updated_at && self.metadata.updated_at.is_none() && revisions.len() > 1 {
+            let timezone = self.metadata.created_at.timezone();
+            self.metadata.updated_at = Some(revisions[0].date.with_timezone(&timezone));
+        }
+        revisions.truncate(config.revisions);
+        self.revisions = revisions;
+        self
+    }
+
+    /// Generate the url of the post from the given permalink pattern.
+    #[must_use]
+    pub fn with_permalink(mut self, permalink: &Permalink) -> Self {
+        self.slug = permalink.slug(&self.metadata);
+        self.relative_url = permalink.relative_url(&self.metadata);
+        self
+    }
+
+    /// Get the base relative links are resolved against, if the post has co-located assets.
+    fn link_base(&self, base_url: &str) -> Option<String> {
+        self.is_bundle().then(|| {
+            format!(
+                "{}/{}/",
+                base_url.trim_end_matches('/'),
+                self.assets_virtual_dir().to_string_lossy()
+            )
+        })
+    }
+
+    /// Get the markdown content of the post without its front matter.
+    pub fn content(&self) -> &str {
+        FrontMatter::split(&self.source).content()
+    }
+
     #[cfg(test)]
     pub fn from_markdown_file_without_path(source: impl AsRef<str>) -> Result<Self> {
         let source = source.as_ref().trim().to_string();
-        let metadata = PostMetadata::from_markdown(&source);
-        Ok(Self { metadata, source })
+        let metadata = PostMetadata::from_markdown(&source, Tz::UTC, || Ok(Utc::now()))?;
+        Ok(Self {
+            path: PathBuf::default(),
+            slug: Permalink::default().slug(&metadata),
+            relative_url: Permalink::default().relative_url(&metadata),
+            metadata,
+            source,
+            assets: Vec::new(),
+            revisions: Vec::new(),
+        })
     }
 
-    pub fn from_markdown_file(path: Cow<Path>) -> Result<Self> {
-        let source = read_to_string(&path)?.trim().to_string();
-        let metadata = PostMetadata::from_markdown(&source);
-        Ok(Self { metadata, source })
+    /// Load a post from a markdown file, reading datetimes without an offset in the given timezone.
+    ///
+    /// `created_at_fallback` is called with the path of posts that don't set `created_at`.
+    pub fn from_markdown_file(
+        path: Cow<Path>,
+        timezone: Tz,
+        created_at_fallback: impl FnOnce(&Path) -> Result<DateTime<Utc>>,
+    ) -> Result<Self> {
+        let source = read_to_string(&path)
+            .with_context(|| format!("Unable to read {path:?}"))?
+            .trim()
+            .to_string();
+        let metadata =
+            PostMetadata::from_markdown(&source, timezone, || created_at_fallback(&path))
+                .with_context(|| format!("Invalid metadata in {path:?}"))?;
+        Ok(Self {
+            path: path.into(),
+            slug: Permalink::default().slug(&metadata),
+            relative_url: Permalink::default().relative_url(&metadata),
+            metadata,
+            source,
+            assets: Vec::new(),
+            revisions: Vec::new(),
+        })
     }
 
-    /// Render the post to html
-    pub fn to_html(&self) -> Result<String> {
-        // Allow unsafe HTML code in posts
-        let render = ComrakRenderOptions {
-            unsafe_: true,
-            ..Default::default()
-        };
-        let extension = ComrakExtensionOptions {
-            table: true,
-            autolink: true,
-            footnotes: true,
-            header_ids: Some(String::default()),
-            ..Default::default()
-        };
-        let options = ComrakOptions {
-            render,
-            extension,
-            ..Default::default()
-        };
-        let html = comrak::markdown_to_html(&self.source, &options);
-        Ok(html.trim().to_string())
+    /// Load a post bundle from its `index.md` and the files next to it.
+    pub fn from_bundle(
+        index_path: Cow<Path>,
+        assets: Vec<StaticFile>,
+        timezone: Tz,
+        created_at_fallback: impl FnOnce(&Path) -> Result<DateTime<Utc>>,
+    ) -> Result<Self> {
+        Ok(Self {
+            assets,
+            ..Self::from_markdown_file(index_path, timezone, created_at_fallback)?
+        })
     }
 
-    /// Render the post to minified spec-compliant html
-    pub fn to_html_minified(&self) -> Result<String> {
-        let html = self.to_html()?;
+    /// Render the post to html and collect its headings
+    ///
+    /// Relative links of post bundles are resolved to the copied assets below `base_url`,
+    /// images with resized variants in `images` are rendered as responsive images.
+    pub fn to_html(
+        &self,
+        renderer: &MarkdownRenderer,
+        base_url: &str,
+        images: Option<&ResponsiveImages>,
+    ) -> Result<RenderedMarkdown> {
+        renderer.render_document(self.content(), self.link_base(base_url).as_deref(), images)
+    }
+
+    /// Render the post to minified spec-compliant html and collect its headings
+    pub fn to_html_minified(
+        &self,
+        renderer: &MarkdownRenderer,
+        base_url: &str,
+        images: Option<&ResponsiveImages>,
+    ) -> Result<RenderedMarkdown> {
+        let rendered = self.to_html(renderer, base_url, images)?;
+        Ok(RenderedMarkdown {
+            html: Self::minify_html(&rendered.html)?,
+            ..rendered
+        })
+    }
+
+    /// Render the explicit summary of the post to minified html, if it has one.
+    ///
+    /// The summary is either the content preceding a `<!-- more -->` marker
+    /// or the `summary` metadata, in that order.
+    pub fn summary_to_html_minified(
+        &self,
+        renderer: &MarkdownRenderer,
+        base_url: &str,
+        images: Option<&ResponsiveImages>,
+    ) -> Result<Option<String>> {
+        let content = self.content();
+        let summary = RE_MORE
+            .find(content)
+            .map(|marker| &content[..marker.start()])
+            .or(self.metadata.summary.as_deref());
+        summary
+            .map(|summary| {
+                let html = renderer.render(summary, self.link_base(base_url).as_deref(), images)?;
+                Self::minify_html(&html)
+            })
+            .transpose()
+    }
+
+    pub(super) fn minify_html(html: &str) -> Result<String> {
         let minify_config = minify_html::Cfg::spec_compliant();
         let minified_html = String::from_utf8(minify(html.as_bytes(), &minify_config))?;
         Ok(minified_html)
     }
 
-    pub fn safe_name(&self) -> String {
-        self.metadata.safe_name()
+    pub fn slug(&self) -> &str {
+        &self.slug
     }
 }
 
 // Scaffolding
 impl Post {
-    pub fn scaffold(name: Cow<str>) -> Result<()> {
+    pub fn scaffold(name: Cow<str>, timezone: Tz) -> Result<()> {
         let metadata = PostMetadata {
             title: name.to_string(),
+            created_at: Utc::now().with_timezone(&timezone),
             ..Default::default()
         };
-        let safe_name = metadata.safe_name();
+        let safe_name = Permalink::default().slug(&metadata);
+        if safe_name.is_empty() {
+            bail!("Unable to name a post {name:?}, the title must contain letters or digits");
+        }
         log::debug!("Scaffolding post {name:?} at posts/{safe_name}.md");
         let source = format!(
             "{metadata}\n# {name}\nWork in progress",
@@ -94,21 +273,14 @@ impl Post {
     }
 }
 
-impl TryFromFile for Post {
-    fn try_from_file(path: Cow<Path>) -> Result<Self>
-    where
-        Self: Sized,
-    {
-        Self::from_markdown_file(path)
-    }
-}
-
 #[cfg(test)]
 mod test {
     use anyhow::Result;
-    use chrono::NaiveDateTime;
+    use chrono::{NaiveDateTime, TimeZone, Utc};
+
+    use crate::blog::config::BlogHistoryConfig;
 
-    use super::Post;
+    use super::{MarkdownRenderer, Post, Revision};
 
     #[test]
     fn post_from_markdown() -> Result<()> {
@@ -134,8 +306,11 @@ mod test {
 - Bar";
         let post = Post::from_markdown_file_without_path(source)?;
         assert_eq!(post.metadata.title, "Hello world");
-        assert_eq!(post.metadata.created_at, NaiveDateTime::default());
-        assert_eq!(post.metadata.published, true);
+        assert_eq!(
+            post.metadata.created_at.naive_local(),
+            NaiveDateTime::default()
+        );
+        assert!(post.metadata.published);
         assert_eq!(post.source, source_expected);
         Ok(())
     }
@@ -151,17 +326,17 @@ mod test {
 
 - Foo
 - Bar";
-        let source_html = r"<h1>Hello world</h1>
+        let source_html = r##"<h1><a href="#hello-world" aria-hidden="true" class="anchor" id="hello-world"></a>Hello world</h1>
 <blockquote>
 <p>This is a test!</p>
 </blockquote>
 <ul>
 <li>Foo</li>
 <li>Bar</li>
-</ul>"
+</ul>"##
             .trim();
         let post = Post::from_markdown_file_without_path(source)?;
-        let html = post.to_html()?;
+        let html = post.to_html(&MarkdownRenderer::default(), "", None)?.html;
         assert_eq!(html, source_html);
         Ok(())
     }
@@ -177,18 +352,112 @@ mod test {
 
 - Foo
 - Bar";
-        let source_html = r"<h1>Hello world</h1><blockquote><p>This is a test!</blockquote><ul><li>Foo<li>Bar</ul>";
+        let source_html = r"<h1><a aria-hidden=true class=anchor href=#hello-world id=hello-world></a>Hello world</h1><blockquote><p>This is a test!</blockquote><ul><li>Foo<li>Bar</ul>";
         let post = Post::from_markdown_file_without_path(source)?;
-        let html = post.to_html_minified()?;
+        let html = post
+            .to_html_minified(&MarkdownRenderer::default(), "", None)?
+            .html;
         assert_eq!(html, source_html);
         Ok(())
     }
 
+    #[test]
+    fn post_to_html_without_front_matter() -> Result<()> {
+        let source = r"+++
+title = 'Hello world'
++++
+Hello";
+        let post = Post::from_markdown_file_without_path(source)?;
+        assert_eq!(post.metadata.title, "Hello world");
+        assert_eq!(
+            post.to_html(&MarkdownRenderer::default(), "", None)?.html,
+            "<p>Hello</p>"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn post_summary() -> Result<()> {
+        let source = r"Hello **world**
+
+<!-- more -->
+
+More content";
+        let post = Post::from_markdown_file_without_path(source)?;
+        assert_eq!(
+            post.summary_to_html_minified(&MarkdownRenderer::default(), "", None)?
+                .as_deref(),
+            Some("<p>Hello <strong>world</strong>")
+        );
+
+        let source = r"[//]: # (summary: A short summary)
+
+Content";
+        let post = Post::from_markdown_file_without_path(source)?;
+        assert_eq!(
+            post.summary_to_html_minified(&MarkdownRenderer::default(), "", None)?
+                .as_deref(),
+            Some("<p>A short summary")
+        );
+
+        let post = Post::from_markdown_file_without_path("Content")?;
+        assert_eq!(
+            post.summary_to_html_minified(&MarkdownRenderer::default(), "", None)?,
+            None
+        );
+        Ok(())
+    }
+
     #[test]
     fn post_safe_name() -> Result<()> {
         let source = r"[//]: # (title: Hello world)";
         let post = Post::from_markdown_file_without_path(source)?;
-        assert_eq!(post.safe_name(), "hello_world");
+        assert_eq!(post.slug(), "hello_world");
+
+        let source = r"[//]: # (title: Hello world)
+[//]: # (slug: Hello-World!)";
+        let post = Post::from_markdown_file_without_path(source)?;
+        assert_eq!(post.slug(), "hello-world");
+        Ok(())
+    }
+
+    #[test]
+    fn post_history() -> Result<()> {
+        let revision = |day, summary: &str| Revision {
+            date: Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap(),
+            summary: summary.into(),
+        };
+        let revisions = vec![revision(9, "Fix typo"), revision(5, "Add post")];
+        let config = BlogHistoryConfig {
+            git_updated_at: true,
+            revisions: 1,
+        };
+
+        let post = Post::from_markdown_file_without_path("Hello")?
+            .with_history(revisions.clone(), &config);
+        assert_eq!(
+            post.metadata
+                .updated_at
+                .map(|updated_at| updated_at.to_rfc3339())
+                .as_deref(),
+            Some("2024-03-09T12:00:00+00:00")
+        );
+        assert_eq!(post.revisions().len(), 1);
+        assert_eq!(post.revisions()[0].summary, "Fix typo");
+
+        // Explicit metadata wins, posts that were only added have not been updated
+        let post = Post::from_markdown_file_without_path("[//]: # (updated_at: 2024-04-01)")?
+            .with_history(revisions.clone(), &config);
+        assert_eq!(
+            post.metadata
+                .updated_at
+                .map(|updated_at| updated_at.date_naive().to_string())
+                .as_deref(),
+            Some("2024-04-01")
+        );
+        let post = Post::from_markdown_file_without_path("Hello")?
+            .with_history(revisions[1..].to_vec(), &config);
+        assert_eq!(post.metadata.updated_at, None);
         Ok(())
     }
 }
@@ -1,30 +1,72 @@
-use std::collections::HashMap;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use itertools::Itertools;
+use serde_json::Value;
 
-use chrono::{Local, NaiveDateTime};
-use once_cell::sync::Lazy;
-use regex::Regex;
-
-static RE_COMMENT: Lazy<Regex> = Lazy::new(|| {
-    let re = r"(?im)^\[[/]{2}\]: # \((?P<key>.*?):\s+(?P<value>.*?)\)$";
-    Regex::new(re).unwrap()
-});
+use super::{
+    front_matter::{FrontMatter, FrontMatterValues},
+    slug::{sanitize_slug, slugify},
+};
 
 const DT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
+/// Metadata keys handled by crablog itself, everything else ends up in `extra`.
+const KNOWN_KEYS: &[&str] = &[
+    "title",
+    "published",
+    "created_at",
+    "updated_at",
+    "tags",
+    "categories",
+    "summary",
+    "slug",
+    "aliases",
+    "author",
+    "authors",
+    "publish_at",
+    "expires_at",
+];
+
 #[derive(Debug)]
 pub struct PostMetadata {
     pub title: String,
     pub published: bool,
-    pub created_at: NaiveDateTime,
+    pub created_at: DateTime<Tz>,
+    /// Time of the last revision of the post
+    pub updated_at: Option<DateTime<Tz>>,
+    /// Time before which the post is left out of production builds
+    pub publish_at: Option<DateTime<Tz>>,
+    /// Time from which the post is left out of production builds
+    pub expires_at: Option<DateTime<Tz>>,
+    pub tags: Vec<String>,
+    pub categories: Vec<String>,
+    /// Markdown summary used instead of the beginning of the post
+    pub summary: Option<String>,
+    /// Slug used in the url of the post instead of one generated from the title
+    pub slug: Option<String>,
+    /// Former urls of the post relative to the base url, redirecting to its current url
+    pub aliases: Vec<String>,
+    /// Ids of the authors of the post, from either the `author` or the `authors` key
+    pub authors: Vec<String>,
+    /// Custom metadata for use in themes
+    pub extra: FrontMatterValues,
 }
 
 impl PostMetadata {
-    pub fn safe_name(&self) -> String {
-        self.title
-            .trim()
-            .to_lowercase()
-            .replace(' ', "_")
-            .replace('/', "")
+    /// Whether the post is published and scheduled to be online at the given time.
+    pub fn is_live_at(&self, now: DateTime<Utc>) -> bool {
+        self.published
+            && self.publish_at.is_none_or(|publish_at| publish_at <= now)
+            && self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+
+    /// Get the slug of the post, generating it from the title unless it is set explicitly.
+    pub fn slug(&self, separator: char) -> String {
+        match &self.slug {
+            Some(slug) => sanitize_slug(slug, separator),
+            None => slugify(&self.title, separator),
+        }
     }
 }
 
@@ -33,7 +75,17 @@ impl Default for PostMetadata {
         Self {
             title: String::default(),
             published: false,
-            created_at: Local::now().naive_local(),
+            created_at: Utc::now().with_timezone(&Tz::UTC),
+            updated_at: None,
+            publish_at: None,
+            expires_at: None,
+            tags: Vec::default(),
+            categories: Vec::default(),
+            summary: None,
+            slug: None,
+            aliases: Vec::default(),
+            authors: Vec::default(),
+            extra: FrontMatterValues::default(),
         }
     }
 }
@@ -51,58 +103,195 @@ impl PostMetadata {
         str.push_str(&to_markdown_comment("title", &self.title));
         str.push_str(&to_markdown_comment(
             "created_at",
-            &self.created_at.format(DT_FORMAT),
+            self.created_at.format(DT_FORMAT),
         ));
         str.push_str(&to_markdown_comment("published", self.published));
+        if !self.tags.is_empty() {
+            str.push_str(&to_markdown_comment("tags", self.tags.join(", ")));
+        }
+        if !self.categories.is_empty() {
+            str.push_str(&to_markdown_comment(
+                "categories",
+                self.categories.join(", "),
+            ));
+        }
         str
     }
 
-    pub fn from_markdown(source: impl AsRef<str>) -> Self {
-        let source = source.as_ref();
-
-        // Extract metadata from source
-        let map = source
-            .lines()
-            .filter_map(|line| {
-                RE_COMMENT.captures(line).and_then(|captures| {
-                    let key = captures.name("key");
-                    let value = captures.name("value");
-                    key.and_then(|key| value.map(|value| (key, value)))
-                })
-            })
-            .fold(HashMap::new(), |mut accum, (key, value)| {
-                accum.entry(key.as_str()).or_insert(value.as_str());
-                accum
-            });
+    /// Parse the metadata of a post, reading datetimes without an offset in the given timezone.
+    ///
+    /// `created_at_fallback` is only called if the post doesn't set `created_at`.
+    pub fn from_markdown(
+        source: impl AsRef<str>,
+        timezone: Tz,
+        created_at_fallback: impl FnOnce() -> Result<DateTime<Utc>>,
+    ) -> Result<Self> {
+        let values = FrontMatter::split(source.as_ref()).parse()?;
+        Self::from_front_matter(&values, timezone, created_at_fallback)
+    }
 
-        // Handle known metadata
-        let title = map
-            .get("title")
-            .map(ToString::to_string)
-            .unwrap_or_default();
-        let published = map
-            .get("published")
-            .and_then(|value| value.parse().ok())
-            .unwrap_or_default();
-        let created_at = map
-            .get("created_at")
-            .and_then(|value| NaiveDateTime::parse_from_str(value, DT_FORMAT).ok())
-            .unwrap_or_else(|| Local::now().naive_local());
+    /// Extract known metadata from parsed front matter values.
+    pub fn from_front_matter(
+        values: &FrontMatterValues,
+        timezone: Tz,
+        created_at_fallback: impl FnOnce() -> Result<DateTime<Utc>>,
+    ) -> Result<Self> {
+        let title = get_string(values, "title")?.unwrap_or_default();
+        let published = get_bool(values, "published")?.unwrap_or_default();
+        let created_at = match get_datetime(values, "created_at", timezone)? {
+            Some(created_at) => created_at,
+            None => created_at_fallback()?.with_timezone(&timezone),
+        };
+        let updated_at = get_datetime(values, "updated_at", timezone)?;
+        let publish_at = get_datetime(values, "publish_at", timezone)?;
+        let expires_at = get_datetime(values, "expires_at", timezone)?;
+        let tags = get_list(values, "tags")?;
+        let categories = get_list(values, "categories")?;
+        let summary = get_string(values, "summary")?;
+        let slug = get_string(values, "slug")?.filter(|slug| !slug.trim().is_empty());
+        let aliases = get_list(values, "aliases")?
+            .into_iter()
+            .map(|alias| parse_alias(&alias))
+            .collect::<Result<_>>()?;
+        let authors = get_list(values, "author")?
+            .into_iter()
+            .chain(get_list(values, "authors")?)
+            .unique()
+            .collect();
+        let extra = values
+            .iter()
+            .filter(|(key, _)| !KNOWN_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
 
-        Self {
+        Ok(Self {
             title,
             published,
             created_at,
+            updated_at,
+            publish_at,
+            expires_at,
+            tags,
+            categories,
+            summary,
+            slug,
+            aliases,
+            authors,
+            extra,
+        })
+    }
+}
+
+pub(super) fn get_string(values: &FrontMatterValues, key: &str) -> Result<Option<String>> {
+    match values.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(value @ (Value::Number(_) | Value::Bool(_))) => Ok(Some(value.to_string())),
+        Some(value) => bail!("Expected `{key}` to be a string, got {value}"),
+    }
+}
+
+pub(super) fn get_bool(values: &FrontMatterValues, key: &str) -> Result<Option<bool>> {
+    match values.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(value)) => Ok(Some(*value)),
+        // YAML 1.1 spellings, which YAML 1.2 parses as strings
+        Some(Value::String(value)) => match value.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" => Ok(Some(true)),
+            "false" | "no" | "off" => Ok(Some(false)),
+            _ => bail!("Expected `{key}` to be true or false, got {value:?}"),
+        },
+        Some(value) => bail!("Expected `{key}` to be a boolean, got {value}"),
+    }
+}
+
+fn get_datetime(
+    values: &FrontMatterValues,
+    key: &str,
+    timezone: Tz,
+) -> Result<Option<DateTime<Tz>>> {
+    let Some(value) = get_string(values, key)? else {
+        return Ok(None);
+    };
+    parse_datetime(&value, timezone)
+        .map(Some)
+        .with_context(|| format!("Expected `{key}` to be a date, got {value:?}"))
+}
+
+/// Parse a datetime in any of the formats accepted in post metadata.
+///
+/// RFC 3339 timestamps are converted to the given timezone, other datetimes are local to it.
+pub fn parse_datetime(value: &str, timezone: Tz) -> Result<DateTime<Tz>> {
+    let value = value.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&timezone));
+    }
+    let datetime = parse_naive_datetime(value)?;
+    // Times skipped by daylight saving don't exist, repeated ones resolve to the first
+    timezone
+        .from_local_datetime(&datetime)
+        .earliest()
+        .with_context(|| format!("{datetime} does not exist in {timezone}"))
+}
+
+fn parse_naive_datetime(value: &str) -> Result<NaiveDateTime> {
+    for format in [DT_FORMAT, "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return Ok(datetime);
         }
     }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")?;
+    Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default())
+}
+
+/// Turn an alias into a url relative to the base url, rejecting urls outside of the blog.
+fn parse_alias(alias: &str) -> Result<String> {
+    let relative_url = alias.trim_start_matches('/');
+    let is_valid = !relative_url.is_empty()
+        && !relative_url.contains("://")
+        && !relative_url.contains('\\')
+        && relative_url.split('/').all(|segment| segment != "..");
+    if !is_valid {
+        bail!("Expected `aliases` to contain paths like /posts/old_title.html, got {alias:?}");
+    }
+    Ok(relative_url.to_string())
+}
+
+fn get_list(values: &FrontMatterValues, key: &str) -> Result<Vec<String>> {
+    let items = match values.get(key) {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(value)) => value.split(',').map(ToString::to_string).collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(item) => Ok(item.clone()),
+                Value::Number(_) | Value::Bool(_) => Ok(item.to_string()),
+                _ => bail!("Expected `{key}` to be a list of strings, got {item}"),
+            })
+            .collect::<Result<_>>()?,
+        Some(value) => bail!("Expected `{key}` to be a list, got {value}"),
+    };
+    Ok(items
+        .iter()
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(ToString::to_string)
+        .collect())
 }
 
 #[cfg(test)]
 mod test {
-    use chrono::NaiveDateTime;
+    use anyhow::{bail, Result};
+    use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+    use chrono_tz::{Europe::Berlin, Tz};
+    use serde_json::json;
 
     use super::PostMetadata;
 
+    fn now() -> Result<DateTime<Utc>> {
+        Ok(Utc::now())
+    }
+
     #[test]
     fn post_metadata_from_markdown() {
         let data = PostMetadata::from_markdown(
@@ -111,10 +300,121 @@ mod test {
 [//]: # (created_at: 1970-01-01 00:00:00)
 [//]: # (published: true)
         ",
-        );
+            Tz::UTC,
+            now,
+        )
+        .unwrap();
+        assert_eq!(data.title, "Hello world");
+        assert_eq!(data.created_at.naive_local(), NaiveDateTime::default());
+        assert!(data.published);
+        assert!(data.tags.is_empty());
+    }
+
+    #[test]
+    fn post_metadata_taxonomies_from_markdown() {
+        let data = PostMetadata::from_markdown(
+            r"
+[//]: # (title: Hello world)
+[//]: # (tags: rust,  static sites ,)
+[//]: # (categories: Programming)
+        ",
+            Tz::UTC,
+            now,
+        )
+        .unwrap();
+        assert_eq!(data.tags, vec!["rust", "static sites"]);
+        assert_eq!(data.categories, vec!["Programming"]);
+    }
+
+    #[test]
+    fn post_metadata_from_toml_front_matter() {
+        let data = PostMetadata::from_markdown(
+            r#"+++
+title = "Hello world"
+created_at = 1970-01-01T00:00:00
+published = true
+tags = ["rust", "static sites"]
++++
+# Hello world"#,
+            Tz::UTC,
+            now,
+        )
+        .unwrap();
+        assert_eq!(data.title, "Hello world");
+        assert_eq!(data.created_at.naive_local(), NaiveDateTime::default());
+        assert!(data.published);
+        assert_eq!(data.tags, vec!["rust", "static sites"]);
+        assert!(data.extra.is_empty());
+    }
+
+    #[test]
+    fn post_metadata_extra() {
+        let data = PostMetadata::from_markdown(
+            r#"+++
+title = "Hello world"
+subtitle = "A first post"
+[hero_image]
+src = "hero.jpg"
++++"#,
+            Tz::UTC,
+            now,
+        )
+        .unwrap();
+        assert_eq!(data.extra.len(), 2);
+        assert_eq!(data.extra["subtitle"], json!("A first post"));
+        assert_eq!(data.extra["hero_image"], json!({ "src": "hero.jpg" }));
+    }
+
+    #[test]
+    fn post_metadata_from_yaml_front_matter() {
+        let data = PostMetadata::from_markdown(
+            r"---
+title: Hello world
+created_at: 1970-01-01
+categories: [Programming]
+---
+# Hello world",
+            Tz::UTC,
+            now,
+        )
+        .unwrap();
         assert_eq!(data.title, "Hello world");
-        assert_eq!(data.created_at, NaiveDateTime::default());
-        assert_eq!(data.published, true);
+        assert_eq!(data.created_at.naive_local(), NaiveDateTime::default());
+        assert!(!data.published);
+        assert_eq!(data.categories, vec!["Programming"]);
+    }
+
+    #[test]
+    fn post_metadata_yaml_booleans() {
+        let data = PostMetadata::from_markdown("---\npublished: yes\n---\n", Tz::UTC, now);
+        assert!(data.unwrap().published);
+        let data = PostMetadata::from_markdown("---\npublished: Off\n---\n", Tz::UTC, now);
+        assert!(!data.unwrap().published);
+    }
+
+    #[test]
+    fn post_metadata_rejects_invalid_values() {
+        let data = PostMetadata::from_markdown("+++\ntags = { rust = true }\n+++\n", Tz::UTC, now);
+        assert!(data.is_err());
+        let data = PostMetadata::from_markdown("---\npublished: maybe\n---\n", Tz::UTC, now);
+        assert!(data.is_err());
+        let data =
+            PostMetadata::from_markdown("---\naliases: [../../etc/passwd]\n---\n", Tz::UTC, now);
+        assert!(data.is_err());
+    }
+
+    #[test]
+    fn post_metadata_aliases() {
+        let data = PostMetadata::from_markdown(
+            "+++\naliases = [\"/posts/helo_world.html\", \"2023/01/hello/\"]\n+++\n",
+            Tz::UTC,
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            data.aliases,
+            vec!["posts/helo_world.html", "2023/01/hello/"]
+        );
     }
 
     #[test]
@@ -122,7 +422,8 @@ mod test {
         let data = PostMetadata {
             title: "Hello world".into(),
             published: false,
-            created_at: NaiveDateTime::default(),
+            created_at: Tz::UTC.from_utc_datetime(&NaiveDateTime::default()),
+            ..Default::default()
         };
         let markdown = data.to_markdown();
         let mut lines = markdown.lines();
@@ -133,4 +434,87 @@ mod test {
         );
         assert_eq!(lines.next(), Some("[//]: # (published: false)"));
     }
+
+    #[test]
+    fn post_metadata_schedule() {
+        let data = PostMetadata::from_markdown(
+            "+++\npublished = true\npublish_at = 2024-03-01\nexpires_at = 2024-04-01T12:00:00\n+++\n",
+            Tz::UTC,
+            now,
+        )
+        .unwrap();
+        let at = |value| {
+            super::parse_datetime(value, Tz::UTC)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        assert!(!data.is_live_at(at("2024-02-29 23:59")));
+        assert!(data.is_live_at(at("2024-03-01")));
+        assert!(!data.is_live_at(at("2024-04-01 12:00")));
+
+        let draft =
+            PostMetadata::from_markdown("+++\npublish_at = 2024-03-01\n+++\n", Tz::UTC, now)
+                .unwrap();
+        assert!(!draft.is_live_at(at("2024-03-02")));
+    }
+
+    #[test]
+    fn post_metadata_timezones() {
+        let data = PostMetadata::from_markdown(
+            "+++\ncreated_at = 2024-07-01T10:00:00\nupdated_at = 2024-12-01\npublish_at = \"2024-07-01T10:00:00Z\"\n+++\n",
+            Berlin,
+            now,
+        )
+        .unwrap();
+        assert_eq!(data.created_at.to_rfc3339(), "2024-07-01T10:00:00+02:00");
+        assert_eq!(
+            data.updated_at
+                .map(|updated_at| updated_at.to_rfc3339())
+                .as_deref(),
+            Some("2024-12-01T00:00:00+01:00")
+        );
+        let publish_at = data.publish_at.unwrap();
+        assert_eq!(publish_at.to_rfc3339(), "2024-07-01T12:00:00+02:00");
+        assert_eq!(
+            publish_at.with_timezone(&Utc),
+            Utc.with_ymd_and_hms(2024, 7, 1, 10, 0, 0).unwrap()
+        );
+
+        // 02:30 is skipped when daylight saving time starts
+        assert!(super::parse_datetime("2024-03-31 02:30", Berlin).is_err());
+    }
+
+    #[test]
+    fn post_metadata_created_at_fallback() {
+        let fallback = || Ok(Utc.with_ymd_and_hms(2024, 7, 1, 10, 0, 0).unwrap());
+        let data =
+            PostMetadata::from_markdown("+++\ntitle = \"Hello\"\n+++\n", Berlin, fallback).unwrap();
+        assert_eq!(data.created_at.to_rfc3339(), "2024-07-01T12:00:00+02:00");
+
+        let data = PostMetadata::from_markdown("+++\ntitle = \"Hello\"\n+++\n", Tz::UTC, || {
+            bail!("`created_at` is missing")
+        });
+        assert!(data.is_err());
+        let data =
+            PostMetadata::from_markdown("+++\ncreated_at = 2024-07-01\n+++\n", Tz::UTC, || {
+                bail!("`created_at` is missing")
+            });
+        assert!(data.is_ok());
+    }
+
+    #[test]
+    fn post_metadata_authors() {
+        let data =
+            PostMetadata::from_markdown("+++\nauthor = \"jane\"\n+++\n", Tz::UTC, now).unwrap();
+        assert_eq!(data.authors, vec!["jane"]);
+
+        let data = PostMetadata::from_markdown(
+            "---\nauthor: jane\nauthors: [john, jane]\n---\n",
+            Tz::UTC,
+            now,
+        )
+        .unwrap();
+        assert_eq!(data.authors, vec!["jane", "john"]);
+        assert!(data.extra.is_empty());
+    }
 }
@@ -0,0 +1,42 @@
+use deunicode::deunicode;
+
+/// Turn arbitrary text into a string that is safe to use in file names and urls.
+///
+/// Text is transliterated to ASCII and every run of other characters than
+/// letters and digits is replaced by the separator.
+pub fn slugify(text: impl AsRef<str>, separator: char) -> String {
+    join_words(text.as_ref(), separator, |c| c.is_ascii_alphanumeric())
+}
+
+/// Make an explicitly chosen slug safe to use, keeping its hyphens and underscores.
+pub fn sanitize_slug(slug: impl AsRef<str>, separator: char) -> String {
+    join_words(slug.as_ref(), separator, |c| {
+        c.is_ascii_alphanumeric() || c == '-' || c == '_'
+    })
+}
+
+fn join_words(text: &str, separator: char, is_word_char: impl Fn(char) -> bool) -> String {
+    deunicode(text)
+        .to_lowercase()
+        .split(|c: char| !is_word_char(c))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(&separator.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sanitize_slug, slugify};
+
+    #[test]
+    fn slug_slugify() {
+        assert_eq!(slugify("Hello world", '_'), "hello_world");
+        assert_eq!(
+            slugify("What's new in 1.0? #rust", '-'),
+            "what-s-new-in-1-0-rust"
+        );
+        assert_eq!(slugify("  Crème brûlée: a/b  ", '_'), "creme_brulee_a_b");
+        assert_eq!(slugify("Привет мир", '-'), "privet-mir");
+        assert_eq!(sanitize_slug("My-Old_Post!", '-'), "my-old_post");
+    }
+}
@@ -0,0 +1,39 @@
+use std::{
+    fs::read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+/// A file copied into the build verbatim, e.g. from the blog's `static/` directory.
+#[derive(Debug)]
+pub struct StaticFile {
+    path: PathBuf,
+    virtual_path: PathBuf,
+}
+
+impl StaticFile {
+    /// Create a static file from its path and the directory it is copied from.
+    pub fn new(path: PathBuf, base_path: impl AsRef<Path>) -> Result<Self> {
+        let base_path = base_path.as_ref();
+        let virtual_path = path
+            .strip_prefix(base_path)
+            .with_context(|| format!("Static file {path:?} is outside of {base_path:?}"))?
+            .to_path_buf();
+        Ok(Self { path, virtual_path })
+    }
+
+    /// Get the path of the source file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the path of the file relative to the directory it is copied from.
+    pub fn virtual_path(&self) -> &Path {
+        &self.virtual_path
+    }
+
+    pub fn read(&self) -> Result<Vec<u8>> {
+        read(&self.path).with_context(|| format!("Unable to read static file {:?}", self.path))
+    }
+}
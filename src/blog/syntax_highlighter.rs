@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use comrak::adapters::SyntaxHighlighterAdapter;
+use itertools::Itertools;
+use once_cell::sync::Lazy;
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{Theme, ThemeSet},
+    html::{
+        css_for_theme_with_class_style, styled_line_to_highlighted_html, ClassStyle,
+        ClassedHTMLGenerator, IncludeBackground,
+    },
+    parsing::{SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
+};
+
+use super::{
+    config::{BlogHighlightConfig, HighlightStyle},
+    html::{escape, opening_tag},
+};
+
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+/// Prefix of the classes emitted in [`HighlightStyle::Classes`] mode.
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+/// Highlights fenced code blocks at build time using the bundled syntaxes.
+#[derive(Debug)]
+pub struct SyntaxHighlighter {
+    theme: &'static Theme,
+    style: HighlightStyle,
+}
+
+impl SyntaxHighlighter {
+    pub fn new(config: &BlogHighlightConfig) -> Result<Self> {
+        let theme = THEME_SET.themes.get(&config.theme).ok_or_else(|| {
+            anyhow!(
+                "Unknown highlighting theme {:?}, available themes: {}",
+                config.theme,
+                THEME_SET.themes.keys().join(", ")
+            )
+        })?;
+        Ok(Self {
+            theme,
+            style: config.style,
+        })
+    }
+
+    /// Get the stylesheet needed for highlighted code, if code is highlighted by classes.
+    pub fn stylesheet(&self) -> Result<Option<String>> {
+        match self.style {
+            HighlightStyle::Inline => Ok(None),
+            HighlightStyle::Classes => Ok(Some(css_for_theme_with_class_style(
+                self.theme,
+                CLASS_STYLE,
+            )?)),
+        }
+    }
+
+    fn find_syntax(lang: Option<&str>, code: &str) -> &'static SyntaxReference {
+        lang.filter(|lang| !lang.is_empty())
+            .and_then(|lang| SYNTAX_SET.find_syntax_by_token(lang))
+            .or_else(|| SYNTAX_SET.find_syntax_by_first_line(code))
+            .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text())
+    }
+
+    fn highlight_inline(&self, syntax: &SyntaxReference, code: &str) -> Result<String> {
+        let mut highlighter = HighlightLines::new(syntax, self.theme);
+        let mut html = String::with_capacity(code.len());
+        for line in LinesWithEndings::from(code) {
+            let regions = highlighter.highlight_line(line, &SYNTAX_SET)?;
+            html.push_str(&styled_line_to_highlighted_html(
+                &regions,
+                IncludeBackground::No,
+            )?);
+        }
+        Ok(html)
+    }
+
+    fn highlight_classes(&self, syntax: &SyntaxReference, code: &str) -> Result<String> {
+        let mut generator =
+            ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAX_SET, CLASS_STYLE);
+        for line in LinesWithEndings::from(code) {
+            generator.parse_html_for_line_which_includes_newline(line)?;
+        }
+        Ok(generator.finalize())
+    }
+}
+
+impl SyntaxHighlighterAdapter for SyntaxHighlighter {
+    fn highlight(&self, lang: Option<&str>, code: &str) -> String {
+        let syntax = Self::find_syntax(lang, code);
+        let html = match self.style {
+            HighlightStyle::Inline => self.highlight_inline(syntax, code),
+            HighlightStyle::Classes => self.highlight_classes(syntax, code),
+        };
+        html.unwrap_or_else(|error| {
+            log::warn!("Unable to highlight code block: {error}");
+            escape(code)
+        })
+    }
+
+    fn build_pre_tag(&self, attributes: &HashMap<String, String>) -> String {
+        let mut attributes = attributes.clone();
+        match self.style {
+            HighlightStyle::Inline => {
+                if let Some(color) = self.theme.settings.background {
+                    let style = format!(
+                        "background-color:#{:02x}{:02x}{:02x};",
+                        color.r, color.g, color.b
+                    );
+                    attributes.insert("style".into(), style);
+                }
+            }
+            HighlightStyle::Classes => {
+                let class = attributes
+                    .get("class")
+                    .map_or("hl-code".into(), |class| format!("hl-code {class}"));
+                attributes.insert("class".into(), class);
+            }
+        }
+        opening_tag("pre", &attributes)
+    }
+
+    fn build_code_tag(&self, attributes: &HashMap<String, String>) -> String {
+        opening_tag("code", attributes)
+    }
+}
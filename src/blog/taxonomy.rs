@@ -0,0 +1,95 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use super::{config::SlugSeparator, slug::slugify, Post, PostMetadata};
+
+/// A way of grouping posts by terms from their metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter)]
+pub enum Taxonomy {
+    Tags,
+    Categories,
+}
+
+/// A single term of a taxonomy and all posts using it.
+#[derive(Debug)]
+pub struct Term<'a> {
+    pub name: String,
+    pub posts: Vec<&'a Post>,
+}
+
+impl Taxonomy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Tags => "tags",
+            Self::Categories => "categories",
+        }
+    }
+
+    /// Get the terms of this taxonomy used by a post.
+    pub fn terms<'a>(&self, metadata: &'a PostMetadata) -> &'a [String] {
+        match self {
+            Self::Tags => &metadata.tags,
+            Self::Categories => &metadata.categories,
+        }
+    }
+
+    /// Path of the page listing all terms.
+    pub fn index_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}/index.html", self.name()))
+    }
+
+    /// Path of the page listing all posts of a term.
+    ///
+    /// The `index` slug is reserved for the overview page,
+    /// so a term with that slug gets a suffix.
+    pub fn term_path(&self, term: impl AsRef<str>) -> PathBuf {
+        let mut slug = slugify(term, SlugSeparator::default().as_char());
+        if slug == "index" {
+            slug.push_str("_term");
+        }
+        PathBuf::from(format!("{}/{slug}.html", self.name()))
+    }
+
+    /// Group posts by term, keyed by term slug.
+    ///
+    /// Terms that only differ in case or whitespace are merged,
+    /// the first spelling encountered is used as the term name.
+    pub fn group_posts<'a>(
+        &self,
+        posts: impl Iterator<Item = &'a Post>,
+    ) -> BTreeMap<String, Term<'a>> {
+        let mut terms = BTreeMap::<String, Term>::new();
+        for post in posts {
+            for term in self.terms(post.metadata()) {
+                terms
+                    .entry(slugify(term, SlugSeparator::default().as_char()))
+                    .or_insert_with(|| Term {
+                        name: term.clone(),
+                        posts: Vec::new(),
+                    })
+                    .posts
+                    .push(post);
+            }
+        }
+        terms
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::Taxonomy;
+
+    #[test]
+    fn taxonomy_term_path() {
+        assert_eq!(Taxonomy::Tags.index_path(), Path::new("tags/index.html"));
+        assert_eq!(
+            Taxonomy::Tags.term_path("Static Sites"),
+            Path::new("tags/static_sites.html")
+        );
+        assert_eq!(
+            Taxonomy::Categories.term_path("Index"),
+            Path::new("categories/index_term.html")
+        );
+    }
+}
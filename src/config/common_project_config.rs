@@ -1,10 +1,13 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    blog::{config::BlogConfig, Blog},
+    blog::{
+        config::{AuthorConfig, BlogConfig},
+        Blog,
+    },
     theme::{config::ThemeConfig, Theme},
     traits::TryLoadConfig,
 };
@@ -18,6 +21,9 @@ pub struct CommonProjectConfig {
     pub(crate) blog_config: Option<BlogConfig>,
     #[serde(rename = "theme")]
     pub(crate) theme_config: Option<ThemeConfig>,
+    /// Profiles of the authors of a blog, keyed by the id posts refer to them with
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) authors: BTreeMap<String, AuthorConfig>,
 }
 
 impl CommonProjectConfig {
@@ -25,7 +31,7 @@ impl CommonProjectConfig {
         self.blog_config
             .clone()
             .context("Configuration file does not contain a blog section")
-            .and_then(Blog::from_config)
+            .and_then(|config| Blog::from_config(config, self.authors.clone()))
     }
 
     pub fn to_theme(&self) -> Result<Theme> {
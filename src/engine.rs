@@ -1,8 +1,18 @@
+mod build_cache;
+mod build_dependency;
 mod build_engine;
 mod build_environment;
 mod build_file;
 mod data;
+mod dependency_graph;
+mod feed;
+mod image_processor;
+mod redirect;
 mod renderer;
+mod robots;
+mod sitemap;
+mod text;
+mod xml;
 
 pub use build_engine::BuildEngine;
 pub use build_environment::BuildEnvironment;
@@ -0,0 +1,85 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read_to_string, write},
+    path::Path,
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{build_file::BuildFile, dependency_graph::DependencyGraph, BuildEnvironment};
+
+pub const CACHE_DIRECTORY: &str = "./.crablog";
+const CACHE_FILENAME: &str = "build-cache.toml";
+
+/// Fingerprints of the outputs written by the previous build.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BuildCache {
+    version: String,
+    env: BuildEnvironment,
+    outputs: BTreeMap<String, String>,
+}
+
+impl BuildCache {
+    pub fn new(env: BuildEnvironment) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            env,
+            outputs: BTreeMap::new(),
+        }
+    }
+
+    /// Load the cache of the previous build.
+    ///
+    /// Falls back to an empty cache if the previous build used a different
+    /// environment or crablog version, so that everything gets rebuilt.
+    pub fn load(env: BuildEnvironment) -> Self {
+        let path = Path::new(CACHE_DIRECTORY).join(CACHE_FILENAME);
+        let cache = read_to_string(path)
+            .ok()
+            .and_then(|content| toml::from_str::<Self>(&content).ok());
+        match cache {
+            Some(cache) if cache.env == env && cache.version == env!("CARGO_PKG_VERSION") => cache,
+            _ => {
+                log::debug!("No usable build cache found, rebuilding everything");
+                Self::new(env)
+            }
+        }
+    }
+
+    /// Create a cache from the outputs of the current build.
+    pub fn from_dependency_graph(env: BuildEnvironment, graph: &DependencyGraph) -> Self {
+        let mut cache = Self::new(env);
+        for virtual_path in graph.outputs() {
+            if let Some(fingerprint) = graph.fingerprint(virtual_path) {
+                cache.outputs.insert(Self::key(virtual_path), fingerprint);
+            }
+        }
+        cache
+    }
+
+    pub fn save(&self) -> Result<()> {
+        create_dir_all(CACHE_DIRECTORY)?;
+        let path = Path::new(CACHE_DIRECTORY).join(CACHE_FILENAME);
+        write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Check whether an output of the previous build can be reused,
+    /// because none of its dependencies changed in the meantime.
+    pub fn is_up_to_date(&self, virtual_path: &Path, graph: &DependencyGraph) -> bool {
+        let previous_fingerprint = self.outputs.get(&Self::key(virtual_path));
+        previous_fingerprint.is_some()
+            && previous_fingerprint == graph.fingerprint(virtual_path).as_ref()
+            && BuildFile::target_path(virtual_path).is_file()
+    }
+
+    /// Iterate over the virtual paths of all outputs of the previous build.
+    pub fn outputs(&self) -> impl Iterator<Item = &Path> {
+        self.outputs.keys().map(Path::new)
+    }
+
+    fn key(virtual_path: &Path) -> String {
+        virtual_path.to_string_lossy().to_string()
+    }
+}
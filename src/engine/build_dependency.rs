@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+use crate::theme::LayoutKind;
+
+/// An input that influences the contents of a build output.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BuildDependency {
+    /// Markdown source of the post at the given path
+    Post(PathBuf),
+    /// Markdown source of the standalone page at the given path
+    Page(PathBuf),
+    /// Titles and urls of the pages in the menu
+    Menu,
+    /// Theme layout of the given kind
+    Layout(LayoutKind),
+    /// Set of enabled theme features
+    Features,
+    /// Blog metadata and configuration
+    BlogMetadata,
+    /// Theme style or script with the given file name
+    Resource(PathBuf),
+    /// Static file at the given source path
+    Asset(PathBuf),
+}
@@ -1,23 +1,66 @@
-use std::{path::Path, time::Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use anyhow::Result;
+use chrono::NaiveDate;
+use itertools::Itertools;
+use rayon::prelude::*;
+use strum::IntoEnumIterator;
 
 use crate::{
-    blog::Blog,
+    blog::{
+        config::HighlightStyle, url_to_virtual_path, Author, Blog, Page, Post, ResponsiveImages,
+        StaticFile, Taxonomy,
+    },
     engine::{
-        data::{BaseDataBuilder, PostData, PostPageData},
+        data::{
+            AuthorData, AuthorPageData, BaseDataBuilder, FeedLinkData, MenuItemData, PageData,
+            PageMetaData, PagePageData, PaginationData, PostData, PostPageData, TaxonomyPageData,
+            TermPageData, TermPostsData,
+        },
         renderer::Renderer,
     },
     theme::LayoutKind,
 };
 
-use super::{build_file::BuildFile, data::IndexPageData, BuildEnvironment};
+use super::{
+    build_cache::BuildCache,
+    build_dependency::BuildDependency,
+    build_file::BuildFile,
+    data::IndexPageData,
+    dependency_graph::DependencyGraph,
+    feed::Feed,
+    image_processor::{ImageCache, PostImage},
+    redirect::{render_netlify_redirects, render_nginx_map, render_redirect_page, Redirect},
+    robots::render_robots_txt,
+    sitemap::Sitemap,
+    BuildEnvironment,
+};
+
+/// Output path of the stylesheet for code highlighted by classes.
+const HIGHLIGHT_STYLESHEET: &str = "highlight.css";
+
+/// Output path of the redirects in the format of Netlify and Cloudflare Pages.
+const NETLIFY_REDIRECTS: &str = "_redirects";
+
+/// Output path of the nginx map of redirects.
+const NGINX_REDIRECTS: &str = "redirects.nginx.conf";
+
+/// Rendered posts, keyed by the path of their source.
+type PostDataMap<'a> = HashMap<&'a Path, PostData>;
 
 #[derive(Debug)]
 pub struct BuildEngine {
     env: BuildEnvironment,
     blog: Blog,
     build_files: Vec<BuildFile>,
+    build_cache: BuildCache,
+    dependency_graph: DependencyGraph,
+    /// Images referenced by posts that resized variants are generated for, keyed by post path
+    post_images: HashMap<PathBuf, Vec<PostImage>>,
 }
 
 impl BuildEngine {
@@ -26,6 +69,9 @@ impl BuildEngine {
             env,
             blog,
             build_files: Vec::new(),
+            build_cache: BuildCache::load(env),
+            dependency_graph: DependencyGraph::default(),
+            post_images: HashMap::new(),
         }
     }
 
@@ -43,11 +89,32 @@ impl BuildEngine {
                 features.join(", ")
             }
         },);
-        self.build_index()?;
-        self.build_posts()?;
-        self.build_resources()?;
+        self.dependency_graph = DependencyGraph::new(&self.blog, self.env)?;
+        self.post_images = self.find_post_images();
+        self.register_outputs()?;
+        let build_files = {
+            let posts = self.blog.iter_posts(self.env).collect::<Vec<_>>();
+            let post_data = self.build_post_data(&posts)?;
+            let mut build_files = Vec::new();
+            build_files.extend(self.build_index(&posts, &post_data)?);
+            build_files.extend(self.build_posts(&posts, &post_data)?);
+            build_files.extend(self.build_pages()?);
+            build_files.extend(self.build_redirects()?);
+            build_files.extend(self.build_post_assets(&posts)?);
+            build_files.extend(self.build_post_images()?);
+            build_files.extend(self.build_taxonomies(&posts, &post_data)?);
+            build_files.extend(self.build_authors(&posts, &post_data)?);
+            build_files.extend(self.build_feeds(&post_data)?);
+            build_files.extend(self.build_sitemap()?);
+            build_files.extend(self.build_robots_txt()?);
+            build_files.extend(self.build_resources()?);
+            build_files
+        };
+        self.build_files = build_files;
         log::debug!("Writing build output to disk");
         self.write_to_disk()?;
+        self.remove_stale_outputs()?;
+        BuildCache::from_dependency_graph(self.env, &self.dependency_graph).save()?;
         log::debug!(
             "Done (took {:.2}s)",
             Instant::now().duration_since(start_time).as_secs_f32()
@@ -55,104 +122,1051 @@ impl BuildEngine {
         Ok(())
     }
 
-    fn build_index(&mut self) -> Result<()> {
-        log::debug!("Building index");
+    /// Register all outputs of the current build with their dependencies.
+    fn register_outputs(&mut self) -> Result<()> {
+        // Index pages
+        let dependencies = {
+            let mut dependencies = Self::page_dependencies(LayoutKind::Index);
+            dependencies.extend(
+                self.blog
+                    .iter_posts(self.env)
+                    .flat_map(|post| self.post_dependencies(post)),
+            );
+            dependencies
+        };
+        for page in 1..=self.index_page_count() {
+            self.dependency_graph.add_output(
+                Path::new(&PaginationData::page_url(page)),
+                dependencies.clone(),
+            );
+        }
+
+        // Post pages
+        for post in self.blog.iter_posts(self.env) {
+            let dependencies = {
+                let mut dependencies = Self::page_dependencies(LayoutKind::Post);
+                dependencies.extend(self.post_dependencies(post));
+                dependencies
+            };
+            self.dependency_graph
+                .add_output(&post.virtual_path(), dependencies);
+        }
+
+        // Standalone pages
+        if self.has_page_layout() {
+            for page in self.blog.pages() {
+                let virtual_path = page.virtual_path();
+                if self.dependency_graph.has_output(virtual_path) {
+                    log::warn!(
+                        "Skipping page {:?}: Conflicts with build output {virtual_path:?}",
+                        page.path()
+                    );
+                    continue;
+                }
+                let mut dependencies = Self::page_dependencies(LayoutKind::Page);
+                dependencies.push(BuildDependency::Page(page.path().to_path_buf()));
+                self.dependency_graph.add_output(virtual_path, dependencies);
+            }
+        } else if !self.blog.pages().is_empty() {
+            log::warn!("Skipping pages: Theme has no page layout");
+        }
+
+        // Redirects from former urls of posts
+        for post in self.blog.iter_posts(self.env) {
+            for alias in &post.metadata().aliases {
+                let virtual_path = url_to_virtual_path(alias);
+                if self.dependency_graph.has_output(&virtual_path) {
+                    log::warn!(
+                        "Skipping alias {alias:?} of {:?}: Conflicts with build output {virtual_path:?}",
+                        post.path()
+                    );
+                    continue;
+                }
+                self.dependency_graph
+                    .add_output(&virtual_path, Self::redirect_dependencies(post));
+            }
+        }
 
-        let output = {
-            // Get layouts
-            let theme = self.blog.theme_bundle();
-            let base_layout = theme.get_layout(LayoutKind::Base)?;
-            let index_layout = theme.get_layout(LayoutKind::Index)?;
+        // Assets of post bundles
+        for post in self.blog.iter_posts(self.env) {
+            let assets_dir = post.assets_virtual_dir();
+            for asset in post.assets() {
+                let virtual_path = assets_dir.join(asset.virtual_path());
+                if self.dependency_graph.has_output(&virtual_path) {
+                    log::warn!(
+                        "Skipping post asset {:?}: Conflicts with build output {virtual_path:?}",
+                        asset.path()
+                    );
+                    continue;
+                }
+                let dependencies = vec![BuildDependency::Asset(asset.path().to_path_buf())];
+                self.dependency_graph
+                    .add_output(&virtual_path, dependencies);
+            }
+        }
 
-            // Build post data from posts
-            let posts = self
+        // Taxonomy overview and term pages
+        if self.has_taxonomy_layouts() {
+            let all_posts = self
                 .blog
                 .iter_posts(self.env)
-                .filter_map(|post| PostData::try_from(post).ok())
+                .flat_map(|post| self.post_dependencies(post))
                 .collect::<Vec<_>>();
+            for taxonomy in Taxonomy::iter() {
+                let terms = taxonomy.group_posts(self.blog.iter_posts(self.env));
+                if terms.is_empty() {
+                    continue;
+                }
+                let virtual_path = taxonomy.index_path();
+                if self.dependency_graph.has_output(&virtual_path) {
+                    log::warn!(
+                        "Skipping {} overview: Conflicts with build output {virtual_path:?}",
+                        taxonomy.name()
+                    );
+                } else {
+                    let mut dependencies = Self::page_dependencies(LayoutKind::Taxonomy);
+                    dependencies.extend(all_posts.iter().cloned());
+                    self.dependency_graph
+                        .add_output(&virtual_path, dependencies);
+                }
+                for term in terms.values() {
+                    let virtual_path = taxonomy.term_path(&term.name);
+                    if self.dependency_graph.has_output(&virtual_path) {
+                        log::warn!(
+                            "Skipping {} term {:?}: Conflicts with build output {virtual_path:?}",
+                            taxonomy.name(),
+                            term.name
+                        );
+                        continue;
+                    }
+                    let mut dependencies = Self::page_dependencies(LayoutKind::Term);
+                    dependencies.extend(all_posts.iter().cloned());
+                    self.dependency_graph
+                        .add_output(&virtual_path, dependencies);
+                }
+            }
+        }
 
-            // Build templating data
-            let base_url = self.blog.config().base_url(self.env);
-            let base_data = BaseDataBuilder::new(base_url)
-                .with_metadata(self.blog.config().meta.clone().into())
-                .with_features(self.blog.resolve_features());
-            let index_page_data = IndexPageData { posts };
+        // Author pages
+        if self.has_author_layout() {
+            for author in self.blog.authors() {
+                let dependencies = self
+                    .blog
+                    .iter_posts(self.env)
+                    .filter(|post| author.is_author_of(post))
+                    .flat_map(|post| self.post_dependencies(post))
+                    .collect::<Vec<_>>();
+                if dependencies.is_empty() {
+                    continue;
+                }
+                let virtual_path = author.page_path();
+                if self.dependency_graph.has_output(&virtual_path) {
+                    log::warn!(
+                        "Skipping page of author {:?}: Conflicts with build output {virtual_path:?}",
+                        author.id()
+                    );
+                    continue;
+                }
+                let mut page_dependencies = Self::page_dependencies(LayoutKind::Author);
+                page_dependencies.extend(dependencies);
+                self.dependency_graph
+                    .add_output(&virtual_path, page_dependencies);
+            }
+        }
 
-            // Render templates
-            let renderer = Renderer::new(self.env, base_layout);
-            renderer.render_index_page(&index_layout.source, base_data, index_page_data)?
+        // Feeds
+        let feed_dependencies = {
+            let mut dependencies = vec![BuildDependency::BlogMetadata];
+            dependencies.extend(
+                self.blog
+                    .iter_posts(BuildEnvironment::Production)
+                    .take(self.blog.config().feeds.limit)
+                    .flat_map(|post| self.post_dependencies(post)),
+            );
+            dependencies
         };
+        for format in &self.blog.config().feeds.formats {
+            self.dependency_graph
+                .add_output(Path::new(format.file_name()), feed_dependencies.clone());
+        }
+
+        // Search engine indexing
+        if self.is_indexable() {
+            let config = self.blog.config();
+            if config.sitemap.enabled {
+                let mut dependencies = vec![BuildDependency::BlogMetadata];
+                dependencies.extend(
+                    self.blog
+                        .iter_posts(self.env)
+                        .map(|post| BuildDependency::Post(post.path().to_path_buf())),
+                );
+                dependencies.extend(
+                    self.blog
+                        .pages()
+                        .iter()
+                        .map(|page| BuildDependency::Page(page.path().to_path_buf())),
+                );
+                self.dependency_graph
+                    .add_output(Path::new("sitemap.xml"), dependencies);
+            }
+            if config.robots.enabled {
+                self.dependency_graph
+                    .add_output(Path::new("robots.txt"), vec![BuildDependency::BlogMetadata]);
+            }
+        }
 
-        // Push build output
-        self.build_files.push(BuildFile::new(
-            Path::new("index.html").into(),
-            output.into(),
-        ));
+        // Redirects for web servers
+        let redirect_config = &self.blog.config().redirects;
+        let redirect_outputs = [
+            (redirect_config.netlify, NETLIFY_REDIRECTS),
+            (redirect_config.nginx, NGINX_REDIRECTS),
+        ];
+        for (enabled, file_name) in redirect_outputs {
+            if !enabled {
+                continue;
+            }
+            let mut dependencies = vec![BuildDependency::BlogMetadata];
+            dependencies.extend(
+                self.blog
+                    .iter_posts(self.env)
+                    .map(|post| BuildDependency::Post(post.path().to_path_buf())),
+            );
+            self.dependency_graph
+                .add_output(Path::new(file_name), dependencies);
+        }
+
+        // Stylesheet for highlighted code
+        if self.has_highlight_stylesheet() {
+            self.dependency_graph.add_output(
+                Path::new(HIGHLIGHT_STYLESHEET),
+                vec![BuildDependency::BlogMetadata],
+            );
+        }
+
+        // Styles and scripts
+        let theme_bundle = self.blog.theme_bundle();
+        let styles = theme_bundle
+            .get_styles()
+            .iter()
+            .map(|style| style.file_name());
+        let scripts = theme_bundle
+            .get_scripts()
+            .iter()
+            .map(|script| script.file_name());
+        for file_name in styles.chain(scripts) {
+            let virtual_path = PathBuf::from(file_name?);
+            let dependencies = vec![BuildDependency::Resource(virtual_path.clone())];
+            self.dependency_graph
+                .add_output(&virtual_path, dependencies);
+        }
+
+        // Theme assets, unless the blog has a static file at the same path
+        let static_files = self.blog.static_files();
+        for asset in theme_bundle.get_assets() {
+            if let Some(static_file) = static_files
+                .iter()
+                .find(|static_file| static_file.virtual_path() == asset.virtual_path())
+            {
+                log::warn!(
+                    "Static file {:?} overrides theme asset {:?}",
+                    static_file.path(),
+                    asset.path()
+                );
+                continue;
+            }
+            let dependencies = vec![BuildDependency::Asset(asset.path().to_path_buf())];
+            self.dependency_graph
+                .add_output(asset.virtual_path(), dependencies);
+        }
+
+        // Static files of the blog
+        for static_file in static_files {
+            if self.dependency_graph.has_output(static_file.virtual_path()) {
+                log::warn!(
+                    "Skipping static file {:?}: Conflicts with build output {:?}",
+                    static_file.path(),
+                    static_file.virtual_path()
+                );
+                continue;
+            }
+            let dependencies = vec![BuildDependency::Asset(static_file.path().to_path_buf())];
+            self.dependency_graph
+                .add_output(static_file.virtual_path(), dependencies);
+        }
+
+        // Resized variants of images referenced by posts
+        for image in self.post_images.values().flatten() {
+            let dependencies = Self::image_variant_dependencies(image);
+            for variant in image.generated_variants() {
+                let virtual_path = variant.virtual_path();
+                if self.dependency_graph.has_output(virtual_path) {
+                    // Images shared by several posts are only registered once
+                    if self.dependency_graph.dependencies(virtual_path) != dependencies {
+                        log::warn!(
+                            "Skipping variant of image {:?}: Conflicts with build output {virtual_path:?}",
+                            image.path()
+                        );
+                    }
+                    continue;
+                }
+                self.dependency_graph
+                    .add_output(virtual_path, dependencies.clone());
+            }
+        }
 
         Ok(())
     }
 
-    fn build_posts(&mut self) -> Result<()> {
+    /// Find the images of all posts that resized variants are generated for.
+    fn find_post_images(&self) -> HashMap<PathBuf, Vec<PostImage>> {
+        let config = &self.blog.config().images;
+        if !config.enabled {
+            return HashMap::new();
+        }
+        self.blog
+            .iter_posts(self.env)
+            .map(|post| {
+                let images = PostImage::find_all(post, self.blog.static_files(), config);
+                (post.path().to_path_buf(), images)
+            })
+            .collect()
+    }
+
+    fn post_images(&self, post: &Post) -> &[PostImage] {
+        self.post_images
+            .get(post.path())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Dependencies of pages showing a rendered post, including the images it references.
+    fn post_dependencies(&self, post: &Post) -> Vec<BuildDependency> {
+        let mut dependencies = vec![BuildDependency::Post(post.path().to_path_buf())];
+        dependencies.extend(
+            self.post_images(post)
+                .iter()
+                .map(|image| BuildDependency::Asset(image.path().to_path_buf())),
+        );
+        dependencies
+    }
+
+    fn image_variant_dependencies(image: &PostImage) -> Vec<BuildDependency> {
+        vec![
+            BuildDependency::Asset(image.path().to_path_buf()),
+            BuildDependency::BlogMetadata,
+        ]
+    }
+
+    /// Redirect pages only depend on the url of the post.
+    fn redirect_dependencies(post: &Post) -> Vec<BuildDependency> {
+        vec![
+            BuildDependency::BlogMetadata,
+            BuildDependency::Post(post.path().to_path_buf()),
+        ]
+    }
+
+    /// Get the redirects from the aliases of posts that don't conflict with other outputs.
+    fn redirects(&self) -> Vec<(&Post, &str)> {
+        self.blog
+            .iter_posts(self.env)
+            .flat_map(|post| {
+                post.metadata()
+                    .aliases
+                    .iter()
+                    .map(move |alias| (post, alias.as_str()))
+            })
+            .filter(|(post, alias)| {
+                self.dependency_graph
+                    .dependencies(&url_to_virtual_path(alias))
+                    == Self::redirect_dependencies(post)
+            })
+            .collect()
+    }
+
+    /// Describe the images of a post by the urls of their variants.
+    fn responsive_images(&self, post: &Post, base_url: &str) -> ResponsiveImages {
+        let sizes = &self.blog.config().images.sizes;
+        self.post_images(post)
+            .iter()
+            .map(|image| {
+                (
+                    image.url().to_string(),
+                    image.to_responsive(base_url, sizes),
+                )
+            })
+            .collect()
+    }
+
+    /// Dependencies shared by all rendered pages.
+    fn page_dependencies(layout_kind: LayoutKind) -> Vec<BuildDependency> {
+        vec![
+            BuildDependency::Layout(LayoutKind::Base),
+            BuildDependency::Layout(layout_kind),
+            BuildDependency::Features,
+            BuildDependency::BlogMetadata,
+            BuildDependency::Menu,
+        ]
+    }
+
+    /// Create templating data shared by all pages.
+    fn base_data(&self) -> BaseDataBuilder {
+        let feeds = self
+            .blog
+            .config()
+            .feeds
+            .formats
+            .iter()
+            .map(|format| FeedLinkData {
+                mime_type: format.mime_type(),
+                relative_url: format.file_name(),
+            })
+            .collect();
+        let base_data = BaseDataBuilder::new(self.blog.config().base_url(self.env))
+            .with_metadata(self.blog.config().meta.clone().into())
+            .with_features(self.blog.resolve_features())
+            .with_feeds(feeds)
+            .with_menu(self.menu());
+        if self.has_highlight_stylesheet() {
+            base_data.with_highlight_stylesheet(HIGHLIGHT_STYLESHEET.into())
+        } else {
+            base_data
+        }
+    }
+
+    /// Links to the standalone pages shown in the menu, ordered by weight.
+    fn menu(&self) -> Vec<MenuItemData> {
+        self.blog
+            .pages()
+            .iter()
+            .filter(|page| page.metadata().menu && self.is_registered_page(page))
+            .map(|page| MenuItemData {
+                title: page.metadata().title.clone(),
+                relative_url: page.virtual_path().to_string_lossy().to_string(),
+            })
+            .collect()
+    }
+
+    /// Pages are only built if the theme supports them.
+    fn has_page_layout(&self) -> bool {
+        self.blog.theme_bundle().has_layout(LayoutKind::Page)
+    }
+
+    /// Whether the page is built, as opposed to skipped for a conflicting output.
+    fn is_registered_page(&self, page: &Page) -> bool {
+        let dependency = BuildDependency::Page(page.path().to_path_buf());
+        self.dependency_graph
+            .dependencies(page.virtual_path())
+            .contains(&dependency)
+    }
+
+    /// Code highlighted by classes needs a stylesheet for the configured color scheme.
+    fn has_highlight_stylesheet(&self) -> bool {
+        let config = &self.blog.config().highlighting;
+        config.enabled && config.style == HighlightStyle::Classes
+    }
+
+    /// Whether the output at the virtual path is a copy of the given file,
+    /// as opposed to a file overriding it or a generated page.
+    fn is_registered_asset(&self, virtual_path: &Path, path: &Path) -> bool {
+        self.dependency_graph.dependencies(virtual_path)
+            == [BuildDependency::Asset(path.to_path_buf())]
+    }
+
+    /// Author pages are only built if the theme supports them.
+    fn has_author_layout(&self) -> bool {
+        self.blog.theme_bundle().has_layout(LayoutKind::Author)
+    }
+
+    /// Whether the page of the author is built, as opposed to skipped for having no posts
+    /// or a conflicting output.
+    fn is_registered_author(&self, author: &Author) -> bool {
+        let dependency = BuildDependency::Layout(LayoutKind::Author);
+        self.dependency_graph
+            .dependencies(&author.page_path())
+            .contains(&dependency)
+    }
+
+    /// Taxonomy pages are only built if the theme supports them.
+    fn has_taxonomy_layouts(&self) -> bool {
+        let theme_bundle = self.blog.theme_bundle();
+        theme_bundle.has_layout(LayoutKind::Taxonomy) && theme_bundle.has_layout(LayoutKind::Term)
+    }
+
+    /// Whether the taxonomy page is built, as opposed to skipped for a conflicting output.
+    fn is_registered_taxonomy_page(&self, virtual_path: &Path, kind: LayoutKind) -> bool {
+        let dependency = BuildDependency::Layout(kind);
+        self.dependency_graph
+            .dependencies(virtual_path)
+            .contains(&dependency)
+    }
+
+    fn index_page_count(&self) -> usize {
+        let post_count = self.blog.iter_posts(self.env).count();
+        self.blog.config().index_page_count(post_count)
+    }
+
+    /// Sitemap and robots.txt are only useful for production builds
+    /// that know the absolute url of the blog.
+    fn is_indexable(&self) -> bool {
+        self.env == BuildEnvironment::Production && self.blog.config().has_absolute_base_url()
+    }
+
+    /// Check whether an output can be reused from the previous build.
+    fn is_up_to_date(&self, virtual_path: &Path) -> bool {
+        let up_to_date = self
+            .build_cache
+            .is_up_to_date(virtual_path, &self.dependency_graph);
+        if up_to_date {
+            log::debug!("Skipping {virtual_path:?}: Up to date");
+        }
+        up_to_date
+    }
+
+    /// Render the posts needed by this build in parallel.
+    ///
+    /// Every post is rendered at most once and shared between all pages
+    /// listing it. Posts that no outdated output depends on are skipped.
+    fn build_post_data<'a>(&self, posts: &[&'a Post]) -> Result<PostDataMap<'a>> {
+        let needed_posts = self
+            .dependency_graph
+            .outputs()
+            .filter(|virtual_path| {
+                !self
+                    .build_cache
+                    .is_up_to_date(virtual_path, &self.dependency_graph)
+            })
+            .flat_map(|virtual_path| self.dependency_graph.dependencies(virtual_path))
+            .filter_map(|dependency| match dependency {
+                BuildDependency::Post(path) => Some(path.as_path()),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let base_url = self.blog.config().base_url(self.env);
+        posts
+            .par_iter()
+            .filter(|post| needed_posts.contains(post.path()))
+            .map(|&post| {
+                let images = self.responsive_images(post, &base_url);
+                let post_data = PostData::new(
+                    post,
+                    self.blog.markdown_renderer(),
+                    &base_url,
+                    Some(&images),
+                    self.blog
+                        .post_authors(post)
+                        .map(|author| {
+                            AuthorData::new(author, &base_url, self.is_registered_author(author))
+                        })
+                        .collect(),
+                )?;
+                Ok((post.path(), post_data))
+            })
+            .collect()
+    }
+
+    fn build_index(&self, posts: &[&Post], post_data: &PostDataMap) -> Result<Vec<BuildFile>> {
+        // Get layouts
         let theme = self.blog.theme_bundle();
         let base_layout = theme.get_layout(LayoutKind::Base)?;
-        let post_layout = theme.get_layout(LayoutKind::Post)?;
+        let index_layout = theme.get_layout(LayoutKind::Index)?;
         let renderer = Renderer::new(self.env, base_layout);
-        let base_url = self.blog.config().base_url(self.env);
 
-        for post in self.blog.iter_posts(self.env) {
-            log::debug!("Building post: {}", post.safe_name());
-            let virtual_path = {
-                let virtual_path = format!("posts/{}.html", post.safe_name());
-                Path::new(&virtual_path).to_path_buf()
+        let total_pages = self.index_page_count();
+        let posts_per_page = self
+            .blog
+            .config()
+            .posts_per_page
+            .filter(|&posts_per_page| posts_per_page > 0)
+            .unwrap_or(posts.len());
+        let mut build_files = Vec::new();
+        for page in 1..=total_pages {
+            let page_url = PaginationData::page_url(page);
+            let virtual_path = Path::new(&page_url);
+            if self.is_up_to_date(virtual_path) {
+                continue;
+            }
+
+            log::debug!("Building index page {page} of {total_pages}");
+
+            // Build templating data
+            let index_page_data = IndexPageData {
+                posts: posts
+                    .iter()
+                    .skip((page - 1) * posts_per_page)
+                    .take(posts_per_page)
+                    .filter_map(|post| post_data.get(post.path()))
+                    .collect(),
+                pagination: PaginationData::new(page, total_pages),
             };
 
-            // Build data for handlebars rendering
-            let base_data = BaseDataBuilder::new(base_url.clone())
-                .with_metadata(self.blog.config().meta.clone().into())
-                .with_features(self.blog.resolve_features());
-            let post_data = PostData::try_from(post)?;
-            let post_page_data = PostPageData {
-                post: Some(post_data),
+            // Render templates
+            let output =
+                renderer.render_page(&index_layout.source, self.base_data(), index_page_data)?;
+            build_files.push(BuildFile::new(virtual_path.into(), output.into()));
+        }
+
+        Ok(build_files)
+    }
+
+    fn build_posts(&self, posts: &[&Post], post_data: &PostDataMap) -> Result<Vec<BuildFile>> {
+        let theme = self.blog.theme_bundle();
+        let base_layout = theme.get_layout(LayoutKind::Base)?;
+        let post_layout = theme.get_layout(LayoutKind::Post)?;
+        let renderer = Renderer::new(self.env, base_layout);
+
+        posts
+            .par_iter()
+            .filter_map(|post| {
+                let virtual_path = post.virtual_path();
+                if self.is_up_to_date(&virtual_path) {
+                    return None;
+                }
+                Some((post, virtual_path, post_data.get(post.path())?))
+            })
+            .map(|(post, virtual_path, post_data)| {
+                log::debug!("Building post: {}", post.slug());
+
+                // Build data for handlebars rendering
+                let post_page_data = PostPageData {
+                    post: Some(post_data),
+                };
+
+                // Describe the page by the post summary
+                let metadata = PageMetaData::from(self.blog.config().meta.clone())
+                    .with_description(post_data.summary_text());
+                let base_data = self.base_data().with_metadata(metadata);
+
+                // Render post page
+                let output =
+                    renderer.render_page(&post_layout.source, base_data, post_page_data)?;
+
+                Ok(BuildFile::new(virtual_path.into(), output.into()))
+            })
+            .collect()
+    }
+
+    fn build_pages(&self) -> Result<Vec<BuildFile>> {
+        if !self.has_page_layout() {
+            return Ok(Vec::new());
+        }
+
+        let theme = self.blog.theme_bundle();
+        let base_layout = theme.get_layout(LayoutKind::Base)?;
+        let page_layout = theme.get_layout(LayoutKind::Page)?;
+        let renderer = Renderer::new(self.env, base_layout);
+
+        self.blog
+            .pages()
+            .par_iter()
+            .filter(|page| {
+                self.is_registered_page(page) && !self.is_up_to_date(page.virtual_path())
+            })
+            .map(|page| {
+                log::debug!("Building page: {:?}", page.virtual_path());
+                let page_data = PageData::new(page, self.blog.markdown_renderer())?;
+                let page_page_data = PagePageData { page: &page_data };
+                let output =
+                    renderer.render_page(&page_layout.source, self.base_data(), page_page_data)?;
+                Ok(BuildFile::new(page.virtual_path().into(), output.into()))
+            })
+            .collect()
+    }
+
+    fn build_redirects(&self) -> Result<Vec<BuildFile>> {
+        let config = self.blog.config();
+        let base_url = config.base_url(self.env);
+        let redirects = self.redirects();
+        let mut build_files = Vec::new();
+
+        for (post, alias) in &redirects {
+            let virtual_path = url_to_virtual_path(alias);
+            if self.is_up_to_date(&virtual_path) {
+                continue;
+            }
+            log::debug!("Building redirect: {alias} -> {}", post.relative_url());
+            let url = format!("{}/{}", base_url.trim_end_matches('/'), post.relative_url());
+            let output = render_redirect_page(&url);
+            build_files.push(BuildFile::new(virtual_path.into(), output.into()));
+        }
+
+        // Redirects for web servers use paths, so they work with any host
+        let base_path = config.base_path();
+        let redirects = redirects
+            .iter()
+            .map(|(post, alias)| Redirect {
+                from: format!("{base_path}/{alias}"),
+                to: format!("{base_path}/{}", post.relative_url()),
+            })
+            .collect::<Vec<_>>();
+        let netlify_path = Path::new(NETLIFY_REDIRECTS);
+        if config.redirects.netlify && !self.is_up_to_date(netlify_path) {
+            log::debug!("Building {NETLIFY_REDIRECTS}");
+            let output = render_netlify_redirects(&redirects)?;
+            build_files.push(BuildFile::new(netlify_path.into(), output.into()));
+        }
+        let nginx_path = Path::new(NGINX_REDIRECTS);
+        if config.redirects.nginx && !self.is_up_to_date(nginx_path) {
+            log::debug!("Building {NGINX_REDIRECTS}");
+            let output = render_nginx_map(&redirects)?;
+            build_files.push(BuildFile::new(nginx_path.into(), output.into()));
+        }
+
+        Ok(build_files)
+    }
+
+    fn build_post_assets(&self, posts: &[&Post]) -> Result<Vec<BuildFile>> {
+        let mut build_files = Vec::new();
+        for post in posts {
+            let assets_dir = post.assets_virtual_dir();
+            for asset in post.assets() {
+                let virtual_path = assets_dir.join(asset.virtual_path());
+                if !self.is_registered_asset(&virtual_path, asset.path())
+                    || self.is_up_to_date(&virtual_path)
+                {
+                    continue;
+                }
+                build_files.push(BuildFile::new(virtual_path.into(), asset.read()?));
+            }
+        }
+        Ok(build_files)
+    }
+
+    /// Encode the outdated variants of post images in parallel.
+    ///
+    /// Encodings are cached across builds, so variants are only re-encoded
+    /// if their source image or the image settings change.
+    fn build_post_images(&self) -> Result<Vec<BuildFile>> {
+        let cache = ImageCache::new(&self.blog.config().images);
+        let images = self
+            .post_images
+            .values()
+            .flatten()
+            .unique_by(|image| image.path())
+            .map(|image| {
+                let dependency = BuildDependency::Asset(image.path().to_path_buf());
+                let fingerprint = self.dependency_graph.input(&dependency).unwrap_or_default();
+                (image, fingerprint)
+            })
+            .collect::<Vec<_>>();
+
+        let build_files = images
+            .par_iter()
+            .map(|&(image, fingerprint)| {
+                let dependencies = Self::image_variant_dependencies(image);
+                let variants = image
+                    .generated_variants()
+                    .filter(|variant| {
+                        self.dependency_graph.dependencies(variant.virtual_path()) == dependencies
+                            && !self.is_up_to_date(variant.virtual_path())
+                    })
+                    .collect::<Vec<_>>();
+                if variants.is_empty() {
+                    return Ok(Vec::new());
+                }
+                let contents = cache.encode(image, &variants, fingerprint)?;
+                Ok(variants
+                    .into_iter()
+                    .zip(contents)
+                    .map(|(variant, content)| {
+                        BuildFile::new(variant.virtual_path().into(), content)
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Images of posts outside of this build stay cached while they exist
+        let sources = self
+            .blog
+            .iter_posts(BuildEnvironment::Development)
+            .flat_map(|post| post.assets())
+            .chain(self.blog.static_files())
+            .map(StaticFile::path);
+        cache.prune(sources, &cache.keys(images.iter().copied()))?;
+        Ok(build_files.into_iter().flatten().collect())
+    }
+
+    fn build_taxonomies(&self, posts: &[&Post], post_data: &PostDataMap) -> Result<Vec<BuildFile>> {
+        if !self.has_taxonomy_layouts() {
+            log::debug!("Skipping taxonomies: Theme has no taxonomy and term layouts");
+            return Ok(Vec::new());
+        }
+
+        let theme = self.blog.theme_bundle();
+        let base_layout = theme.get_layout(LayoutKind::Base)?;
+        let taxonomy_layout = theme.get_layout(LayoutKind::Taxonomy)?;
+        let term_layout = theme.get_layout(LayoutKind::Term)?;
+        let renderer = Renderer::new(self.env, base_layout);
+
+        let mut build_files = Vec::new();
+        for taxonomy in Taxonomy::iter() {
+            let terms = taxonomy
+                .group_posts(posts.iter().copied())
+                .into_values()
+                .filter(|term| {
+                    self.is_registered_taxonomy_page(
+                        &taxonomy.term_path(&term.name),
+                        LayoutKind::Term,
+                    )
+                })
+                .map(|term| TermPostsData {
+                    relative_url: taxonomy.term_path(&term.name).to_string_lossy().to_string(),
+                    posts: term
+                        .posts
+                        .iter()
+                        .filter_map(|post| post_data.get(post.path()))
+                        .collect(),
+                    name: term.name,
+                })
+                .collect::<Vec<_>>();
+
+            // Render term pages
+            for term in &terms {
+                let virtual_path = taxonomy.term_path(&term.name);
+                if self.is_up_to_date(&virtual_path) {
+                    continue;
+                }
+                log::debug!("Building {} term: {}", taxonomy.name(), term.name);
+                let term_page_data = TermPageData {
+                    taxonomy: taxonomy.name(),
+                    term: term.clone(),
+                };
+                let output =
+                    renderer.render_page(&term_layout.source, self.base_data(), term_page_data)?;
+                build_files.push(BuildFile::new(virtual_path.into(), output.into()));
+            }
+
+            // Render overview page
+            let virtual_path = taxonomy.index_path();
+            if terms.is_empty()
+                || !self.is_registered_taxonomy_page(&virtual_path, LayoutKind::Taxonomy)
+                || self.is_up_to_date(&virtual_path)
+            {
+                continue;
+            }
+            log::debug!("Building {} overview", taxonomy.name());
+            let taxonomy_page_data = TaxonomyPageData {
+                taxonomy: taxonomy.name(),
+                terms,
             };
+            let output = renderer.render_page(
+                &taxonomy_layout.source,
+                self.base_data(),
+                taxonomy_page_data,
+            )?;
+            build_files.push(BuildFile::new(virtual_path.into(), output.into()));
+        }
+
+        Ok(build_files)
+    }
+
+    fn build_authors(&self, posts: &[&Post], post_data: &PostDataMap) -> Result<Vec<BuildFile>> {
+        if !self.has_author_layout() {
+            log::debug!("Skipping author pages: Theme has no author layout");
+            return Ok(Vec::new());
+        }
+
+        let theme = self.blog.theme_bundle();
+        let base_layout = theme.get_layout(LayoutKind::Base)?;
+        let author_layout = theme.get_layout(LayoutKind::Author)?;
+        let renderer = Renderer::new(self.env, base_layout);
+        let base_url = self.blog.config().base_url(self.env);
 
-            // Render post page
+        let mut build_files = Vec::new();
+        for author in self.blog.authors() {
+            let virtual_path = author.page_path();
+            if !self.is_registered_author(author) || self.is_up_to_date(&virtual_path) {
+                continue;
+            }
+            log::debug!("Building author page: {}", author.id());
+            let author_page_data = AuthorPageData {
+                author: AuthorData::new(author, &base_url, true),
+                posts: posts
+                    .iter()
+                    .filter(|post| author.is_author_of(post))
+                    .filter_map(|post| post_data.get(post.path()))
+                    .collect(),
+            };
             let output =
-                renderer.render_post_page(&post_layout.source, base_data, post_page_data)?;
+                renderer.render_page(&author_layout.source, self.base_data(), author_page_data)?;
+            build_files.push(BuildFile::new(virtual_path.into(), output.into()));
+        }
+        Ok(build_files)
+    }
 
-            // Push build output
-            let build_file = BuildFile::new(virtual_path.into(), output.into());
-            self.build_files.push(build_file);
+    fn build_feeds(&self, post_data: &PostDataMap) -> Result<Vec<BuildFile>> {
+        let config = self.blog.config();
+        if config.feeds.formats.is_empty() {
+            return Ok(Vec::new());
+        }
+        if self.env == BuildEnvironment::Production && !config.has_absolute_base_url() {
+            log::warn!(
+                "Feeds need an absolute base_url (e.g. https://example.com) to link to posts"
+            );
         }
 
-        Ok(())
+        // Feeds only ever contain published posts
+        let posts = self
+            .blog
+            .iter_posts(BuildEnvironment::Production)
+            .filter_map(|post| post_data.get(post.path()));
+        let feed = Feed::new(config, posts);
+
+        let mut build_files = Vec::new();
+        for format in &config.feeds.formats {
+            let virtual_path = Path::new(format.file_name());
+            if self.is_up_to_date(virtual_path) {
+                continue;
+            }
+            log::debug!("Building feed: {}", format.file_name());
+            let output = feed.render(*format, &config.absolute_url(format.file_name()))?;
+            build_files.push(BuildFile::new(virtual_path.into(), output.into()));
+        }
+
+        Ok(build_files)
+    }
+
+    /// Get the date a post was last changed, for the `lastmod` of the sitemap.
+    fn last_modified(post: &Post) -> NaiveDate {
+        let metadata = post.metadata();
+        metadata
+            .updated_at
+            .unwrap_or(metadata.created_at)
+            .date_naive()
+    }
+
+    fn build_sitemap(&self) -> Result<Option<BuildFile>> {
+        let virtual_path = Path::new("sitemap.xml");
+        if !self.blog.config().sitemap.enabled {
+            return Ok(None);
+        }
+        if !self.is_indexable() {
+            log::debug!("Skipping sitemap: Requires a production build with absolute base_url");
+            return Ok(None);
+        }
+        if self.is_up_to_date(virtual_path) {
+            return Ok(None);
+        }
+
+        log::debug!("Building sitemap");
+        let config = self.blog.config();
+        let mut sitemap = Sitemap::default();
+        let last_modified = self
+            .blog
+            .iter_posts(self.env)
+            .map(Self::last_modified)
+            .max();
+        sitemap.add_url(config.absolute_url(""), last_modified);
+        for post in self.blog.iter_posts(self.env) {
+            sitemap.add_url(
+                config.absolute_url(post.relative_url()),
+                Some(Self::last_modified(post)),
+            );
+        }
+        for page in self.blog.pages() {
+            if self.is_registered_page(page) {
+                sitemap.add_url(
+                    config.absolute_url(page.virtual_path().to_string_lossy()),
+                    None,
+                );
+            }
+        }
+
+        let output = sitemap.to_xml()?;
+        Ok(Some(BuildFile::new(virtual_path.into(), output.into())))
+    }
+
+    fn build_robots_txt(&self) -> Result<Option<BuildFile>> {
+        let virtual_path = Path::new("robots.txt");
+        let config = self.blog.config();
+        if !self.is_indexable() || !config.robots.enabled || self.is_up_to_date(virtual_path) {
+            return Ok(None);
+        }
+
+        log::debug!("Building robots.txt");
+        let sitemap_url = config
+            .sitemap
+            .enabled
+            .then(|| config.absolute_url("sitemap.xml"));
+        let output = render_robots_txt(&config.robots, sitemap_url.as_deref())?;
+        Ok(Some(BuildFile::new(virtual_path.into(), output.into())))
     }
 
-    fn build_resources(&mut self) -> Result<()> {
+    fn build_resources(&self) -> Result<Vec<BuildFile>> {
         let theme_bundle = self.blog.theme_bundle();
+        let mut build_files = Vec::new();
 
         log::debug!("Building style resources");
         for style in theme_bundle.get_styles() {
-            self.build_files.push(BuildFile::new(
-                Path::new(style.file_name()?.as_str()).into(),
-                style.source().into(),
-            ));
+            let virtual_path = PathBuf::from(style.file_name()?);
+            if self.is_up_to_date(&virtual_path) {
+                continue;
+            }
+            build_files.push(BuildFile::new(virtual_path.into(), style.source().into()));
+        }
+
+        if let Some(stylesheet) = self.blog.markdown_renderer().highlight_stylesheet()? {
+            let virtual_path = Path::new(HIGHLIGHT_STYLESHEET);
+            if !self.is_up_to_date(virtual_path) {
+                build_files.push(BuildFile::new(virtual_path.into(), stylesheet.into()));
+            }
         }
 
         log::debug!("Building script resources");
         for script in theme_bundle.get_scripts() {
-            self.build_files.push(BuildFile::new(
-                Path::new(script.file_name()?.as_str()).into(),
-                script.source().into(),
+            let virtual_path = PathBuf::from(script.file_name()?);
+            if self.is_up_to_date(&virtual_path) {
+                continue;
+            }
+            build_files.push(BuildFile::new(virtual_path.into(), script.source().into()));
+        }
+
+        log::debug!("Building theme assets");
+        for asset in theme_bundle.get_assets() {
+            if !self.is_registered_asset(asset.virtual_path(), asset.path())
+                || self.is_up_to_date(asset.virtual_path())
+            {
+                continue;
+            }
+            build_files.push(BuildFile::new(asset.virtual_path().into(), asset.read()?));
+        }
+
+        log::debug!("Building static files");
+        for static_file in self.blog.static_files() {
+            if !self.is_registered_asset(static_file.virtual_path(), static_file.path())
+                || self.is_up_to_date(static_file.virtual_path())
+            {
+                continue;
+            }
+            build_files.push(BuildFile::new(
+                static_file.virtual_path().into(),
+                static_file.read()?,
             ));
         }
 
-        Ok(())
+        Ok(build_files)
     }
 
     pub fn write_to_disk(&self) -> Result<()> {
         for build_file in &self.build_files {
+            log::debug!("Writing {:?}", build_file.virtual_path());
             build_file.write_to_disk()?;
         }
         Ok(())
     }
+
+    /// Remove outputs of the previous build that are no longer produced.
+    fn remove_stale_outputs(&self) -> Result<()> {
+        let current_outputs = self.dependency_graph.outputs().collect::<Vec<_>>();
+        for virtual_path in self.build_cache.outputs() {
+            if current_outputs.contains(&virtual_path) {
+                continue;
+            }
+            let target_path = BuildFile::target_path(virtual_path);
+            if target_path.is_file() {
+                log::debug!("Removing stale output {virtual_path:?}");
+                std::fs::remove_file(target_path)?;
+            }
+        }
+        Ok(())
+    }
 }
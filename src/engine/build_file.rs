@@ -9,24 +9,31 @@ use std::{
 #[derive(Debug)]
 pub struct BuildFile {
     virtual_path: PathBuf,
-    content: String,
+    content: Vec<u8>,
 }
 
 impl BuildFile {
-    pub fn new(virtual_path: Cow<Path>, content: Cow<str>) -> Self {
+    pub fn new(virtual_path: Cow<Path>, content: Vec<u8>) -> Self {
         Self {
             virtual_path: virtual_path.into(),
-            content: content.into(),
+            content,
         }
     }
 
+    /// Get the location of a virtual path inside the build directory.
+    pub fn target_path(virtual_path: impl AsRef<Path>) -> PathBuf {
+        let mut target_dir = Path::new("./build").to_path_buf();
+        target_dir.push(virtual_path);
+        target_dir
+    }
+
+    pub fn virtual_path(&self) -> &Path {
+        &self.virtual_path
+    }
+
     pub fn write_to_disk(&self) -> Result<()> {
         // Construct target path
-        let target_path = {
-            let mut target_dir = Path::new("./build").to_path_buf();
-            target_dir.push(&self.virtual_path);
-            target_dir
-        };
+        let target_path = Self::target_path(&self.virtual_path);
 
         // Create all directories
         {
@@ -38,7 +45,7 @@ impl BuildFile {
 
         // Write file to disk
         let mut file = File::create(target_path)?;
-        file.write_all(self.content.as_bytes())?;
+        file.write_all(&self.content)?;
 
         Ok(())
     }
@@ -1,13 +1,37 @@
+mod author_data;
+mod author_page_data;
 mod base_data;
 mod base_data_builder;
+mod feed_link_data;
 mod index_page_data;
+mod menu_item_data;
+mod page_data;
 mod page_meta_data;
+mod page_page_data;
+mod pagination_data;
 mod post_data;
 mod post_page_data;
+mod revision_data;
+mod taxonomy_page_data;
+mod term_data;
+mod term_page_data;
+mod toc_entry_data;
 
+pub use author_data::AuthorData;
+pub use author_page_data::AuthorPageData;
 pub use base_data::BaseData;
 pub use base_data_builder::BaseDataBuilder;
+pub use feed_link_data::FeedLinkData;
 pub use index_page_data::IndexPageData;
+pub use menu_item_data::MenuItemData;
+pub use page_data::PageData;
 pub use page_meta_data::PageMetaData;
+pub use page_page_data::PagePageData;
+pub use pagination_data::PaginationData;
 pub use post_data::PostData;
 pub use post_page_data::PostPageData;
+pub use revision_data::RevisionData;
+pub use taxonomy_page_data::TaxonomyPageData;
+pub use term_data::{TermData, TermPostsData};
+pub use term_page_data::TermPageData;
+pub use toc_entry_data::TocEntryData;
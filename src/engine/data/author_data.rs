@@ -0,0 +1,55 @@
+use serde::Serialize;
+
+use crate::blog::Author;
+
+/// Profile of an author, linking to the page listing their posts.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorData {
+    pub id: String,
+    pub name: String,
+    pub bio: Option<String>,
+    /// Url of the profile picture, resolved against the base url
+    pub avatar: Option<String>,
+    pub links: Vec<AuthorLinkData>,
+    /// Url of the page listing their posts, if it is built
+    pub relative_url: Option<String>,
+}
+
+/// Link to a profile or website of an author.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorLinkData {
+    pub name: String,
+    pub url: String,
+}
+
+impl AuthorData {
+    pub fn new(author: &Author, base_url: &str, has_page: bool) -> Self {
+        let config = author.config();
+        let avatar = config.avatar.as_ref().map(|avatar| {
+            if avatar.contains("://") {
+                avatar.clone()
+            } else {
+                format!(
+                    "{}/{}",
+                    base_url.trim_end_matches('/'),
+                    avatar.trim_start_matches('/')
+                )
+            }
+        });
+        Self {
+            id: author.id().to_string(),
+            name: author.name().to_string(),
+            bio: config.bio.clone(),
+            avatar,
+            links: config
+                .links
+                .iter()
+                .map(|(name, url)| AuthorLinkData {
+                    name: name.clone(),
+                    url: url.clone(),
+                })
+                .collect(),
+            relative_url: has_page.then(|| author.page_path().to_string_lossy().to_string()),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+use super::{AuthorData, PostData};
+
+#[derive(Debug, Serialize)]
+pub struct AuthorPageData<'a> {
+    pub author: AuthorData,
+    pub posts: Vec<&'a PostData>,
+}
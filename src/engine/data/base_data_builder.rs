@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use serde::Serialize;
 
-use super::{BaseData, PageMetaData};
+use super::{BaseData, FeedLinkData, MenuItemData, PageMetaData};
 
 #[derive(Debug, Default, Serialize)]
 pub struct BaseDataBuilder {
@@ -13,6 +13,12 @@ pub struct BaseDataBuilder {
     metadata: Option<PageMetaData>,
     /// Enabled theme features
     features: HashMap<String, bool>,
+    /// Available feeds
+    feeds: Vec<FeedLinkData>,
+    /// Links to the standalone pages shown in the menu
+    menu: Vec<MenuItemData>,
+    /// Stylesheet for code highlighted by classes
+    highlight_stylesheet: Option<String>,
     /// Source code of the current page
     content: Option<String>,
 }
@@ -42,6 +48,24 @@ impl BaseDataBuilder {
         self
     }
 
+    #[must_use]
+    pub fn with_feeds(mut self, feeds: Vec<FeedLinkData>) -> Self {
+        self.feeds = feeds;
+        self
+    }
+
+    #[must_use]
+    pub fn with_menu(mut self, menu: Vec<MenuItemData>) -> Self {
+        self.menu = menu;
+        self
+    }
+
+    #[must_use]
+    pub fn with_highlight_stylesheet(mut self, relative_url: String) -> Self {
+        self.highlight_stylesheet = Some(relative_url);
+        self
+    }
+
     #[must_use]
     pub fn with_content(mut self, content: String) -> Self {
         self.content = Some(content);
@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+/// Link to a feed, for use in `<link rel="alternate">` tags.
+#[derive(Debug, Serialize)]
+pub struct FeedLinkData {
+    pub mime_type: &'static str,
+    pub relative_url: &'static str,
+}
@@ -1,8 +1,9 @@
 use serde::Serialize;
 
-use super::post_data::PostData;
+use super::{post_data::PostData, PaginationData};
 
 #[derive(Debug, Serialize)]
-pub struct IndexPageData {
-    pub posts: Vec<PostData>,
+pub struct IndexPageData<'a> {
+    pub posts: Vec<&'a PostData>,
+    pub pagination: PaginationData,
 }
@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+/// Link to a standalone page in the menu.
+#[derive(Debug, Clone, Serialize)]
+pub struct MenuItemData {
+    pub title: String,
+    pub relative_url: String,
+}
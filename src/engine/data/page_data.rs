@@ -0,0 +1,24 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::blog::{FrontMatterValues, MarkdownRenderer, Page};
+
+#[derive(Debug, Serialize)]
+pub struct PageData {
+    title: String,
+    html: String,
+    relative_url: String,
+    extra: FrontMatterValues,
+}
+
+impl PageData {
+    pub fn new(page: &Page, renderer: &MarkdownRenderer) -> Result<Self> {
+        let metadata = page.metadata();
+        Ok(Self {
+            title: metadata.title.clone(),
+            html: page.to_html_minified(renderer)?.html,
+            relative_url: page.virtual_path().to_string_lossy().to_string(),
+            extra: metadata.extra.clone(),
+        })
+    }
+}
@@ -9,6 +9,15 @@ pub struct PageMetaData {
     keywords: String,
 }
 
+impl PageMetaData {
+    /// Describe the current page instead of the whole blog.
+    #[must_use]
+    pub fn with_description(mut self, description: impl ToString) -> Self {
+        self.description = description.to_string();
+        self
+    }
+}
+
 impl From<BlogMetadataConfig> for PageMetaData {
     fn from(config: BlogMetadataConfig) -> Self {
         Self {
@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+use super::PageData;
+
+#[derive(Debug, Serialize)]
+pub struct PagePageData<'a> {
+    pub page: &'a PageData,
+}
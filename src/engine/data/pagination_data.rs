@@ -0,0 +1,50 @@
+use serde::Serialize;
+
+/// Position of a page within a paginated listing.
+#[derive(Debug, Serialize)]
+pub struct PaginationData {
+    /// Number of the current page, starting at 1
+    pub current_page: usize,
+    pub total_pages: usize,
+    pub previous_url: Option<String>,
+    pub next_url: Option<String>,
+}
+
+impl PaginationData {
+    pub fn new(current_page: usize, total_pages: usize) -> Self {
+        Self {
+            current_page,
+            total_pages,
+            previous_url: (current_page > 1).then(|| Self::page_url(current_page - 1)),
+            next_url: (current_page < total_pages).then(|| Self::page_url(current_page + 1)),
+        }
+    }
+
+    /// Get the relative url of an index page, starting at 1.
+    pub fn page_url(page: usize) -> String {
+        if page <= 1 {
+            "index.html".to_string()
+        } else {
+            format!("page/{page}.html")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PaginationData;
+
+    #[test]
+    fn pagination_urls() {
+        let first = PaginationData::new(1, 3);
+        assert_eq!(first.previous_url, None);
+        assert_eq!(first.next_url.as_deref(), Some("page/2.html"));
+
+        let second = PaginationData::new(2, 3);
+        assert_eq!(second.previous_url.as_deref(), Some("index.html"));
+        assert_eq!(second.next_url.as_deref(), Some("page/3.html"));
+
+        let last = PaginationData::new(3, 3);
+        assert_eq!(last.next_url, None);
+    }
+}
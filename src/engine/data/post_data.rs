@@ -1,30 +1,135 @@
 use anyhow::Result;
-use chrono::NaiveDateTime;
+use chrono::DateTime;
+use chrono_tz::Tz;
 use serde::Serialize;
 
-use crate::blog::Post;
+use crate::blog::{
+    FrontMatterValues, MarkdownRenderer, Post, RenderedMarkdown, ResponsiveImages, Taxonomy,
+};
+
+use crate::engine::{
+    text::{html_to_text, truncate_words},
+    xml::escape,
+};
+
+use super::{AuthorData, RevisionData, TermData, TocEntryData};
+
+/// Number of words used for summaries of posts without an explicit one.
+const SUMMARY_WORDS: usize = 50;
+
+/// Format of dates shown on the blog, e.g. "March 5, 2024"
+pub(super) const DATE_FORMAT: &str = "%B %-d, %Y";
 
 #[derive(Debug, Serialize)]
 pub struct PostData {
     title: String,
-    created_at: NaiveDateTime,
+    /// Creation time as an RFC 3339 timestamp in the timezone of the blog
+    created_at: DateTime<Tz>,
+    /// Creation date formatted for display
+    date: String,
+    /// Time of the last revision as an RFC 3339 timestamp, if the post has been updated
+    updated_at: Option<DateTime<Tz>>,
+    /// Date of the last revision formatted for display
+    updated_date: Option<String>,
+    /// Commits of the post read from git, newest first
+    revisions: Vec<RevisionData>,
     published: bool,
+    authors: Vec<AuthorData>,
+    tags: Vec<TermData>,
+    categories: Vec<TermData>,
     html: String,
+    /// Headings of the post, nested by level
+    toc: Vec<TocEntryData>,
+    /// Summary of the post as html
+    summary: String,
+    /// Summary of the post as plain text
+    summary_text: String,
     relative_url: String,
+    extra: FrontMatterValues,
 }
 
-impl TryFrom<&Post> for PostData {
-    type Error = anyhow::Error;
+impl PostData {
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn created_at(&self) -> DateTime<Tz> {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> Option<DateTime<Tz>> {
+        self.updated_at
+    }
+
+    pub fn html(&self) -> &str {
+        &self.html
+    }
+
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    pub fn summary_text(&self) -> &str {
+        &self.summary_text
+    }
+
+    pub fn relative_url(&self) -> &str {
+        &self.relative_url
+    }
+}
 
-    fn try_from(post: &Post) -> Result<Self> {
+impl PostData {
+    pub fn new(
+        post: &Post,
+        renderer: &MarkdownRenderer,
+        base_url: &str,
+        images: Option<&ResponsiveImages>,
+        authors: Vec<AuthorData>,
+    ) -> Result<Self> {
         let metadata = post.metadata();
-        let html = post.to_html_minified()?;
+        let RenderedMarkdown { html, headings } =
+            post.to_html_minified(renderer, base_url, images)?;
+        let summary = match post.summary_to_html_minified(renderer, base_url, images)? {
+            Some(summary) => summary,
+            None => format!(
+                "<p>{}</p>",
+                escape(&truncate_words(&html_to_text(&html), SUMMARY_WORDS))
+            ),
+        };
+        let summary_text = html_to_text(&summary);
+        let terms = |taxonomy: Taxonomy| {
+            taxonomy
+                .terms(metadata)
+                .iter()
+                .map(|term| TermData {
+                    name: term.clone(),
+                    relative_url: taxonomy.term_path(term).to_string_lossy().to_string(),
+                })
+                .collect()
+        };
         Ok(Self {
             title: metadata.title.clone(),
             created_at: metadata.created_at,
+            date: metadata.created_at.format(DATE_FORMAT).to_string(),
+            updated_at: metadata.updated_at,
+            updated_date: metadata
+                .updated_at
+                .map(|updated_at| updated_at.format(DATE_FORMAT).to_string()),
+            revisions: post
+                .revisions()
+                .iter()
+                .map(|revision| RevisionData::new(revision, metadata.created_at.timezone()))
+                .collect(),
             published: metadata.published,
-            relative_url: format!("posts/{}.html", post.safe_name()),
+            authors,
+            tags: terms(Taxonomy::Tags),
+            categories: terms(Taxonomy::Categories),
+            relative_url: post.relative_url().to_string(),
             html,
+            toc: TocEntryData::from_headings(&headings),
+            summary,
+            summary_text,
+            extra: metadata.extra.clone(),
         })
     }
 }
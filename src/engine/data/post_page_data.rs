@@ -3,6 +3,6 @@ use serde::Serialize;
 use super::post_data::PostData;
 
 #[derive(Debug, Serialize)]
-pub struct PostPageData {
-    pub post: Option<PostData>,
+pub struct PostPageData<'a> {
+    pub post: Option<&'a PostData>,
 }
@@ -0,0 +1,28 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::blog::Revision;
+
+use super::post_data::DATE_FORMAT;
+
+/// A commit that changed a post.
+#[derive(Debug, Serialize)]
+pub struct RevisionData {
+    /// Commit time as an RFC 3339 timestamp in the timezone of the blog
+    pub committed_at: DateTime<Tz>,
+    /// Commit date formatted for display
+    pub date: String,
+    pub summary: String,
+}
+
+impl RevisionData {
+    pub fn new(revision: &Revision, timezone: Tz) -> Self {
+        let committed_at = revision.date.with_timezone(&timezone);
+        Self {
+            committed_at,
+            date: committed_at.format(DATE_FORMAT).to_string(),
+            summary: revision.summary.clone(),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+use super::TermPostsData;
+
+#[derive(Debug, Serialize)]
+pub struct TaxonomyPageData<'a> {
+    pub taxonomy: &'static str,
+    pub terms: Vec<TermPostsData<'a>>,
+}
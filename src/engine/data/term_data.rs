@@ -0,0 +1,18 @@
+use serde::Serialize;
+
+use super::PostData;
+
+/// Link to a taxonomy term.
+#[derive(Debug, Serialize)]
+pub struct TermData {
+    pub name: String,
+    pub relative_url: String,
+}
+
+/// A taxonomy term with all posts using it.
+#[derive(Debug, Clone, Serialize)]
+pub struct TermPostsData<'a> {
+    pub name: String,
+    pub relative_url: String,
+    pub posts: Vec<&'a PostData>,
+}
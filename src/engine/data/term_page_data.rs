@@ -0,0 +1,9 @@
+use serde::Serialize;
+
+use super::TermPostsData;
+
+#[derive(Debug, Serialize)]
+pub struct TermPageData<'a> {
+    pub taxonomy: &'static str,
+    pub term: TermPostsData<'a>,
+}
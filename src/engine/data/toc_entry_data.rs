@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+use crate::blog::Heading;
+
+/// Entry of the table of contents of a post, containing its subheadings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TocEntryData {
+    pub level: u8,
+    pub text: String,
+    pub id: String,
+    pub children: Vec<TocEntryData>,
+}
+
+impl TocEntryData {
+    /// Nest a flat list of headings by their level.
+    pub fn from_headings(headings: &[Heading]) -> Vec<Self> {
+        fn insert(entries: &mut Vec<TocEntryData>, entry: TocEntryData) {
+            match entries.last_mut() {
+                Some(parent) if parent.level < entry.level => insert(&mut parent.children, entry),
+                _ => entries.push(entry),
+            }
+        }
+
+        let mut toc = Vec::new();
+        for heading in headings {
+            insert(
+                &mut toc,
+                Self {
+                    level: heading.level,
+                    text: heading.text.clone(),
+                    id: heading.id.clone(),
+                    children: Vec::new(),
+                },
+            );
+        }
+        toc
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::blog::Heading;
+
+    use super::TocEntryData;
+
+    #[test]
+    fn toc_entry_data_from_headings() {
+        let headings = [(1, "a"), (2, "b"), (3, "c"), (2, "d"), (1, "e")]
+            .into_iter()
+            .map(|(level, text)| Heading {
+                level,
+                text: text.into(),
+                id: text.into(),
+            })
+            .collect::<Vec<_>>();
+        let toc = TocEntryData::from_headings(&headings);
+        assert_eq!(toc.len(), 2);
+        assert_eq!(toc[0].children.len(), 2);
+        assert_eq!(toc[0].children[0].children[0].text, "c");
+        assert_eq!(toc[0].children[1].text, "d");
+        assert_eq!(toc[1].text, "e");
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+
+use crate::blog::{Blog, Post};
+
+use super::{build_dependency::BuildDependency, BuildEnvironment};
+
+/// Tracks which inputs every build output depends on.
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    /// Fingerprints of all known inputs
+    inputs: HashMap<BuildDependency, u64>,
+    /// Inputs of every output, keyed by virtual path
+    outputs: BTreeMap<PathBuf, Vec<BuildDependency>>,
+}
+
+impl DependencyGraph {
+    /// Fingerprint all inputs of the given blog.
+    pub fn new(blog: &Blog, env: BuildEnvironment) -> Result<Self> {
+        let mut inputs = HashMap::new();
+
+        for post in blog.iter_posts(env) {
+            let dependency = BuildDependency::Post(post.path().to_path_buf());
+            inputs.insert(dependency, post_fingerprint(post));
+            for asset in post.assets() {
+                let dependency = BuildDependency::Asset(asset.path().to_path_buf());
+                inputs.insert(dependency, hash(asset.read()?));
+            }
+        }
+
+        for page in blog.pages() {
+            let dependency = BuildDependency::Page(page.path().to_path_buf());
+            inputs.insert(dependency, hash(page.source()));
+        }
+        let menu = blog
+            .pages()
+            .iter()
+            .filter(|page| page.metadata().menu)
+            .map(|page| (&page.metadata().title, page.virtual_path()))
+            .collect::<Vec<_>>();
+        inputs.insert(BuildDependency::Menu, hash(menu));
+
+        let theme_bundle = blog.theme_bundle();
+        for layout in theme_bundle.get_layouts() {
+            let dependency = BuildDependency::Layout(layout.kind.clone());
+            inputs.insert(dependency, hash(&layout.source));
+        }
+        for style in theme_bundle.get_styles() {
+            let dependency = BuildDependency::Resource(style.file_name()?.into());
+            inputs.insert(dependency, hash(style.source()));
+        }
+        for script in theme_bundle.get_scripts() {
+            let dependency = BuildDependency::Resource(script.file_name()?.into());
+            inputs.insert(dependency, hash(script.source()));
+        }
+        for asset in theme_bundle.get_assets() {
+            let dependency = BuildDependency::Asset(asset.path().to_path_buf());
+            inputs.insert(dependency, hash(asset.read()?));
+        }
+        for static_file in blog.static_files() {
+            let dependency = BuildDependency::Asset(static_file.path().to_path_buf());
+            inputs.insert(dependency, hash(static_file.read()?));
+        }
+
+        inputs.insert(BuildDependency::Features, hash(blog.resolve_features()));
+        let authors = blog
+            .authors()
+            .iter()
+            .map(|author| (author.id(), author.config()))
+            .collect::<Vec<_>>();
+        inputs.insert(
+            BuildDependency::BlogMetadata,
+            hash((
+                serde_json::to_string(blog.config())?,
+                serde_json::to_string(&authors)?,
+            )),
+        );
+
+        Ok(Self {
+            inputs,
+            outputs: BTreeMap::new(),
+        })
+    }
+
+    /// Get the fingerprint of a single input.
+    pub fn input(&self, dependency: &BuildDependency) -> Option<u64> {
+        self.inputs.get(dependency).copied()
+    }
+
+    /// Register an output and the inputs it depends on.
+    pub fn add_output(&mut self, virtual_path: &Path, dependencies: Vec<BuildDependency>) {
+        self.outputs
+            .insert(virtual_path.to_path_buf(), dependencies);
+    }
+
+    pub fn has_output(&self, virtual_path: &Path) -> bool {
+        self.outputs.contains_key(virtual_path)
+    }
+
+    /// Get the inputs of a registered output.
+    pub fn dependencies(&self, virtual_path: &Path) -> &[BuildDependency] {
+        self.outputs
+            .get(virtual_path)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Iterate over the virtual paths of all registered outputs.
+    pub fn outputs(&self) -> impl Iterator<Item = &Path> {
+        self.outputs.keys().map(PathBuf::as_path)
+    }
+
+    /// Compute a fingerprint that changes whenever an input of the output changes.
+    pub fn fingerprint(&self, virtual_path: &Path) -> Option<String> {
+        let dependencies = self.outputs.get(virtual_path)?;
+        let mut hasher = DefaultHasher::new();
+        for dependency in dependencies {
+            dependency.hash(&mut hasher);
+            self.inputs.get(dependency).hash(&mut hasher);
+        }
+        Some(format!("{:016x}", hasher.finish()))
+    }
+}
+
+/// Fingerprint the source of a post along with everything resolved outside of it,
+/// like a `created_at` fallback, its url and its git history.
+fn post_fingerprint(post: &Post) -> u64 {
+    let metadata = post.metadata();
+    let resolved = (metadata.created_at, post.slug(), post.relative_url());
+    let history = (metadata.updated_at, post.revisions());
+    hash((post.source(), resolved, history))
+}
+
+fn hash(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{borrow::Cow, fs::write, path::Path};
+
+    use chrono::{TimeZone, Utc};
+    use chrono_tz::Tz;
+    use tempfile::tempdir;
+
+    use crate::{blog::Post, engine::build_dependency::BuildDependency, theme::LayoutKind};
+
+    use super::{hash, post_fingerprint, DependencyGraph};
+
+    #[test]
+    fn fingerprint_changes_with_dependencies() {
+        let post = BuildDependency::Post("posts/hello.md".into());
+        let layout = BuildDependency::Layout(LayoutKind::Post);
+        let mut graph = DependencyGraph::default();
+        graph.inputs.insert(post.clone(), hash("Hello"));
+        graph.inputs.insert(layout.clone(), hash("<main></main>"));
+        graph.add_output(Path::new("a.html"), vec![post.clone(), layout.clone()]);
+        graph.add_output(Path::new("b.html"), vec![layout.clone()]);
+
+        let a = graph.fingerprint(Path::new("a.html"));
+        let b = graph.fingerprint(Path::new("b.html"));
+        assert!(a.is_some());
+        assert_eq!(graph.fingerprint(Path::new("c.html")), None);
+
+        // Changing a post only affects outputs depending on it
+        graph.inputs.insert(post, hash("Hello world"));
+        assert_ne!(graph.fingerprint(Path::new("a.html")), a);
+        assert_eq!(graph.fingerprint(Path::new("b.html")), b);
+    }
+
+    #[test]
+    fn fingerprint_changes_with_created_at_fallback() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("no_date.md");
+        write(&path, "[//]: # (title: No date)\n\nHello").unwrap();
+        let post = |year| {
+            Post::from_markdown_file(Cow::Borrowed(path.as_path()), Tz::UTC, |_| {
+                Ok(Utc.with_ymd_and_hms(year, 5, 5, 0, 0, 0).unwrap())
+            })
+            .unwrap()
+        };
+
+        assert_eq!(post_fingerprint(&post(2020)), post_fingerprint(&post(2020)));
+        assert_ne!(post_fingerprint(&post(2020)), post_fingerprint(&post(2026)));
+    }
+}
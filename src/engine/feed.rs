@@ -0,0 +1,211 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::blog::config::{BlogConfig, FeedContent, FeedFormat};
+
+use super::{data::PostData, xml::escape};
+
+/// A syndication feed of the most recent posts.
+#[derive(Debug)]
+pub struct Feed {
+    title: String,
+    description: String,
+    site_url: String,
+    updated: DateTime<Utc>,
+    entries: Vec<FeedEntry>,
+}
+
+#[derive(Debug)]
+struct FeedEntry {
+    title: String,
+    url: String,
+    published: DateTime<Utc>,
+    updated: DateTime<Utc>,
+    /// Full html content, omitted if the feed only contains summaries
+    content: Option<String>,
+    summary: String,
+    summary_text: String,
+}
+
+impl FeedEntry {
+    /// Get the full content if available, the html summary otherwise.
+    fn content_or_summary(&self) -> &str {
+        self.content.as_deref().unwrap_or(&self.summary)
+    }
+}
+
+impl Feed {
+    /// Build a feed from rendered posts, newest first.
+    pub fn new<'a>(config: &BlogConfig, posts: impl Iterator<Item = &'a PostData>) -> Self {
+        let entries = posts
+            .take(config.feeds.limit)
+            .map(|post| FeedEntry {
+                title: post.title().to_string(),
+                url: config.absolute_url(post.relative_url()),
+                published: post.created_at().with_timezone(&Utc),
+                updated: post
+                    .updated_at()
+                    .unwrap_or(post.created_at())
+                    .with_timezone(&Utc),
+                content: match config.feeds.content {
+                    FeedContent::Full => Some(post.html().to_string()),
+                    FeedContent::Summary => None,
+                },
+                summary: post.summary().to_string(),
+                summary_text: post.summary_text().to_string(),
+            })
+            .collect::<Vec<_>>();
+        let updated = entries
+            .iter()
+            .map(|entry| entry.updated)
+            .max()
+            .unwrap_or_default();
+        Self {
+            title: config.meta.title.clone(),
+            description: config.meta.description.clone(),
+            site_url: config.absolute_url(""),
+            updated,
+            entries,
+        }
+    }
+
+    /// Render the feed in the given format.
+    pub fn render(&self, format: FeedFormat, feed_url: &str) -> Result<String> {
+        Ok(match format {
+            FeedFormat::Rss => self.to_rss(feed_url)?,
+            FeedFormat::Atom => self.to_atom(feed_url)?,
+            FeedFormat::Json => self.to_json_feed(feed_url)?,
+        })
+    }
+
+    fn to_rss(&self, feed_url: &str) -> Result<String> {
+        let mut xml = String::new();
+        writeln!(xml, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+        writeln!(
+            xml,
+            r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">"#
+        )?;
+        writeln!(xml, "<channel>")?;
+        writeln!(xml, "<title>{}</title>", escape(&self.title))?;
+        writeln!(xml, "<link>{}</link>", escape(&self.site_url))?;
+        writeln!(
+            xml,
+            "<description>{}</description>",
+            escape(&self.description)
+        )?;
+        writeln!(
+            xml,
+            r#"<atom:link href="{}" rel="self" type="{}"/>"#,
+            escape(feed_url),
+            FeedFormat::Rss.mime_type()
+        )?;
+        writeln!(
+            xml,
+            "<lastBuildDate>{}</lastBuildDate>",
+            self.updated.to_rfc2822()
+        )?;
+        for entry in &self.entries {
+            writeln!(xml, "<item>")?;
+            writeln!(xml, "<title>{}</title>", escape(&entry.title))?;
+            writeln!(xml, "<link>{}</link>", escape(&entry.url))?;
+            writeln!(xml, "<guid>{}</guid>", escape(&entry.url))?;
+            writeln!(xml, "<pubDate>{}</pubDate>", entry.published.to_rfc2822())?;
+            writeln!(
+                xml,
+                "<description>{}</description>",
+                escape(entry.content_or_summary())
+            )?;
+            writeln!(xml, "</item>")?;
+        }
+        writeln!(xml, "</channel>")?;
+        write!(xml, "</rss>")?;
+        Ok(xml)
+    }
+
+    fn to_atom(&self, feed_url: &str) -> Result<String> {
+        let mut xml = String::new();
+        writeln!(xml, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+        writeln!(xml, r#"<feed xmlns="http://www.w3.org/2005/Atom">"#)?;
+        writeln!(xml, "<id>{}</id>", escape(&self.site_url))?;
+        writeln!(xml, "<title>{}</title>", escape(&self.title))?;
+        if !self.description.is_empty() {
+            writeln!(xml, "<subtitle>{}</subtitle>", escape(&self.description))?;
+        }
+        writeln!(xml, r#"<link href="{}"/>"#, escape(&self.site_url))?;
+        writeln!(xml, r#"<link href="{}" rel="self"/>"#, escape(feed_url))?;
+        writeln!(xml, "<updated>{}</updated>", self.updated.to_rfc3339())?;
+        for entry in &self.entries {
+            writeln!(xml, "<entry>")?;
+            writeln!(xml, "<id>{}</id>", escape(&entry.url))?;
+            writeln!(xml, "<title>{}</title>", escape(&entry.title))?;
+            writeln!(xml, r#"<link href="{}"/>"#, escape(&entry.url))?;
+            writeln!(
+                xml,
+                "<published>{}</published>",
+                entry.published.to_rfc3339()
+            )?;
+            writeln!(xml, "<updated>{}</updated>", entry.updated.to_rfc3339())?;
+            writeln!(
+                xml,
+                r#"<summary type="html">{}</summary>"#,
+                escape(&entry.summary)
+            )?;
+            if let Some(content) = &entry.content {
+                writeln!(xml, r#"<content type="html">{}</content>"#, escape(content))?;
+            }
+            writeln!(xml, "</entry>")?;
+        }
+        write!(xml, "</feed>")?;
+        Ok(xml)
+    }
+
+    fn to_json_feed(&self, feed_url: &str) -> Result<String> {
+        #[derive(Serialize)]
+        struct JsonFeed<'a> {
+            version: &'static str,
+            title: &'a str,
+            #[serde(skip_serializing_if = "str::is_empty")]
+            description: &'a str,
+            home_page_url: &'a str,
+            feed_url: &'a str,
+            items: Vec<JsonFeedItem<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonFeedItem<'a> {
+            id: &'a str,
+            url: &'a str,
+            title: &'a str,
+            date_published: String,
+            date_modified: String,
+            content_html: &'a str,
+            summary: &'a str,
+        }
+
+        let items = self
+            .entries
+            .iter()
+            .map(|entry| JsonFeedItem {
+                id: &entry.url,
+                url: &entry.url,
+                title: &entry.title,
+                date_published: entry.published.to_rfc3339(),
+                date_modified: entry.updated.to_rfc3339(),
+                content_html: entry.content_or_summary(),
+                summary: &entry.summary_text,
+            })
+            .collect();
+        let feed = JsonFeed {
+            version: "https://jsonfeed.org/version/1.1",
+            title: &self.title,
+            description: &self.description,
+            home_page_url: &self.site_url,
+            feed_url,
+            items,
+        };
+        Ok(serde_json::to_string_pretty(&feed)?)
+    }
+}
@@ -0,0 +1,478 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    fs::{create_dir_all, read, read_dir, remove_file, write},
+    hash::{Hash, Hasher},
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageFormat};
+use itertools::Itertools;
+
+use crate::blog::{
+    config::BlogImageConfig, is_relative_url, MarkdownRenderer, Post, ResponsiveImage, StaticFile,
+};
+
+use super::build_cache::CACHE_DIRECTORY;
+
+/// Directory inside the cache directory encoded variants are kept in.
+const IMAGE_CACHE_DIRECTORY: &str = "images";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VariantFormat {
+    Jpeg,
+    Png,
+    WebP,
+}
+
+impl VariantFormat {
+    /// Get the format of an image from its file extension, if variants can be generated for it.
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "png" => Some(Self::Png),
+            "webp" => Some(Self::WebP),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            VariantFormat::Jpeg => "jpg",
+            VariantFormat::Png => "png",
+            VariantFormat::WebP => "webp",
+        }
+    }
+}
+
+/// A resized or converted version of an image.
+#[derive(Debug, Clone)]
+pub struct ImageVariant {
+    virtual_path: PathBuf,
+    width: u32,
+    format: VariantFormat,
+}
+
+impl ImageVariant {
+    pub fn virtual_path(&self) -> &Path {
+        &self.virtual_path
+    }
+
+    /// Get the name of the cached encoding of the variant.
+    ///
+    /// The name starts with the key of the source image, the rest changes
+    /// whenever the source image or the encoding settings change.
+    fn cache_key(&self, source: &Path, source_fingerprint: u64, quality: u8) -> String {
+        let mut hasher = DefaultHasher::new();
+        (source_fingerprint, self.width, self.format, quality).hash(&mut hasher);
+        format!(
+            "{}-{:016x}.{}",
+            source_cache_key(source),
+            hasher.finish(),
+            self.format.extension()
+        )
+    }
+
+    fn encode(&self, image: &DynamicImage, quality: u8) -> Result<Vec<u8>> {
+        let resized;
+        let image = if image.width() > self.width {
+            resized = image.resize(self.width, u32::MAX, FilterType::Lanczos3);
+            &resized
+        } else {
+            image
+        };
+        let mut output = Vec::new();
+        match self.format {
+            VariantFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8())
+                .write_with_encoder(JpegEncoder::new_with_quality(&mut output, quality))?,
+            VariantFormat::Png => {
+                image.write_to(&mut Cursor::new(&mut output), ImageFormat::Png)?
+            }
+            VariantFormat::WebP => {
+                let rgba = image.to_rgba8();
+                output = webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height())
+                    .encode(quality as f32)
+                    .to_vec();
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Local image referenced by a post, along with the variants generated for it.
+#[derive(Debug, Clone)]
+pub struct PostImage {
+    /// Url of the image in the markdown of the post
+    url: String,
+    path: PathBuf,
+    /// Virtual path the original image is copied to
+    virtual_path: PathBuf,
+    width: u32,
+    height: u32,
+    format: VariantFormat,
+    /// Candidates of the srcset, ordered by width, including the original if it is small enough
+    variants: Vec<ImageVariant>,
+}
+
+impl PostImage {
+    /// Find the local images referenced by a post and plan their variants.
+    ///
+    /// Relative urls refer to assets of post bundles, absolute paths to static files.
+    /// Images that can't be read are left to the browser with a warning.
+    pub fn find_all(
+        post: &Post,
+        static_files: &[StaticFile],
+        config: &BlogImageConfig,
+    ) -> Vec<Self> {
+        MarkdownRenderer::image_urls(post.content())
+            .into_iter()
+            .unique()
+            .filter_map(|url| {
+                let (path, virtual_path) = if let Some(path) = url.strip_prefix('/') {
+                    let static_file = static_files
+                        .iter()
+                        .find(|static_file| static_file.virtual_path() == Path::new(path))?;
+                    (static_file.path(), static_file.virtual_path().to_path_buf())
+                } else if is_relative_url(&url) {
+                    let path = Path::new(url.trim_start_matches("./"));
+                    let asset = post
+                        .assets()
+                        .iter()
+                        .find(|asset| asset.virtual_path() == path)?;
+                    (asset.path(), post.assets_virtual_dir().join(path))
+                } else {
+                    return None;
+                };
+                let format = VariantFormat::from_path(path)?;
+                let (width, height) = image::image_dimensions(path)
+                    .map_err(|error| log::warn!("Unable to read image {path:?}: {error}"))
+                    .ok()?;
+                Some(Self {
+                    variants: Self::plan_variants(&virtual_path, width, format, config),
+                    url,
+                    path: path.to_path_buf(),
+                    virtual_path,
+                    width,
+                    height,
+                    format,
+                })
+            })
+            .collect()
+    }
+
+    /// Images are scaled down to every configured width below their own. The
+    /// original is listed as well, unless it is wider than all configured widths.
+    fn plan_variants(
+        virtual_path: &Path,
+        width: u32,
+        format: VariantFormat,
+        config: &BlogImageConfig,
+    ) -> Vec<ImageVariant> {
+        let mut widths = config
+            .widths
+            .iter()
+            .copied()
+            .filter(|&variant_width| variant_width > 0 && variant_width < width)
+            .sorted()
+            .dedup()
+            .collect::<Vec<_>>();
+        if widths.is_empty() || config.widths.iter().any(|&max_width| max_width >= width) {
+            widths.push(width);
+        }
+
+        let mut formats = vec![format];
+        if config.webp && format != VariantFormat::WebP {
+            formats.push(VariantFormat::WebP);
+        }
+        let stem = virtual_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+        formats
+            .into_iter()
+            .cartesian_product(widths)
+            .map(|(variant_format, variant_width)| {
+                let virtual_path = if variant_format == format && variant_width == width {
+                    virtual_path.to_path_buf()
+                } else {
+                    virtual_path.with_file_name(format!(
+                        "{stem}-{variant_width}w.{}",
+                        variant_format.extension()
+                    ))
+                };
+                ImageVariant {
+                    virtual_path,
+                    width: variant_width,
+                    format: variant_format,
+                }
+            })
+            .collect()
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the path of the source image.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Iterate over the variants that have to be encoded, excluding the copied original.
+    pub fn generated_variants(&self) -> impl Iterator<Item = &ImageVariant> {
+        self.variants
+            .iter()
+            .filter(|variant| variant.virtual_path != self.virtual_path)
+    }
+
+    /// Describe the image with urls of its variants below `base_url`.
+    pub fn to_responsive(&self, base_url: &str, sizes: &str) -> ResponsiveImage {
+        let url = |variant: &ImageVariant| {
+            format!(
+                "{}/{}",
+                base_url.trim_end_matches('/'),
+                variant.virtual_path.to_string_lossy()
+            )
+        };
+        let srcset = |format: VariantFormat| {
+            self.variants
+                .iter()
+                .filter(|variant| variant.format == format)
+                .map(|variant| format!("{} {}w", url(variant), variant.width))
+                .join(", ")
+        };
+        let src = self
+            .variants
+            .iter()
+            .rfind(|variant| variant.format == self.format)
+            .map(url)
+            .unwrap_or_default();
+        let has_webp = self.format != VariantFormat::WebP
+            && self
+                .variants
+                .iter()
+                .any(|variant| variant.format == VariantFormat::WebP);
+        ResponsiveImage {
+            src,
+            srcset: srcset(self.format),
+            webp_srcset: has_webp.then(|| srcset(VariantFormat::WebP)),
+            sizes: sizes.to_string(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// Get the prefix of the cache entries of all variants of a source image.
+fn source_cache_key(source: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Encoded image variants, kept on disk so unchanged images are not re-encoded by every build.
+#[derive(Debug)]
+pub struct ImageCache {
+    directory: PathBuf,
+    quality: u8,
+}
+
+impl ImageCache {
+    pub fn new(config: &BlogImageConfig) -> Self {
+        Self {
+            directory: Path::new(CACHE_DIRECTORY).join(IMAGE_CACHE_DIRECTORY),
+            quality: config.quality.clamp(1, 100),
+        }
+    }
+
+    /// Encode the given variants of an image, reusing cached encodings where possible.
+    ///
+    /// The source image is only decoded if a variant is missing from the cache.
+    pub fn encode(
+        &self,
+        image: &PostImage,
+        variants: &[&ImageVariant],
+        source_fingerprint: u64,
+    ) -> Result<Vec<Vec<u8>>> {
+        let mut decoded = None;
+        variants
+            .iter()
+            .map(|variant| {
+                let cache_path = self.directory.join(variant.cache_key(
+                    &image.path,
+                    source_fingerprint,
+                    self.quality,
+                ));
+                if let Ok(content) = read(&cache_path) {
+                    log::debug!("Using cached image variant {:?}", variant.virtual_path);
+                    return Ok(content);
+                }
+                log::debug!("Encoding image variant {:?}", variant.virtual_path);
+                if decoded.is_none() {
+                    let source = image::open(&image.path)
+                        .with_context(|| format!("Unable to decode image {:?}", image.path))?;
+                    decoded = Some(source);
+                }
+                let content = variant.encode(decoded.as_ref().unwrap(), self.quality)?;
+                create_dir_all(&self.directory)?;
+                write(&cache_path, &content)?;
+                Ok(content)
+            })
+            .collect()
+    }
+
+    /// Get the names of the cache entries of the generated variants of the given images.
+    pub fn keys<'a>(
+        &self,
+        images: impl IntoIterator<Item = (&'a PostImage, u64)>,
+    ) -> HashSet<String> {
+        images
+            .into_iter()
+            .flat_map(|(image, source_fingerprint)| {
+                image.generated_variants().map(move |variant| {
+                    variant.cache_key(&image.path, source_fingerprint, self.quality)
+                })
+            })
+            .collect()
+    }
+
+    /// Remove cached encodings of source images that no longer exist, and encodings of
+    /// images in this build that are not in `keys`, i.e. for an older source or other settings.
+    ///
+    /// Encodings of other images are kept, as they belong to posts that are not part
+    /// of every build, like drafts and scheduled posts.
+    pub fn prune<'a>(
+        &self,
+        sources: impl IntoIterator<Item = &'a Path>,
+        keys: &HashSet<String>,
+    ) -> Result<()> {
+        let Ok(entries) = read_dir(&self.directory) else {
+            return Ok(());
+        };
+        let sources = sources
+            .into_iter()
+            .map(source_cache_key)
+            .collect::<HashSet<_>>();
+        let built_sources = keys
+            .iter()
+            .filter_map(|key| key.split_once('-'))
+            .map(|(source, _)| source)
+            .collect::<HashSet<_>>();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_outdated = match name.split_once('-') {
+                Some((source, _)) => {
+                    !sources.contains(source)
+                        || (built_sources.contains(source) && !keys.contains(&name))
+                }
+                None => true,
+            };
+            if is_outdated {
+                log::debug!("Removing outdated cached image variant {name}");
+                remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashSet,
+        fs::{read_dir, write},
+        path::Path,
+    };
+
+    use tempfile::tempdir;
+
+    use crate::blog::config::BlogImageConfig;
+
+    use super::{source_cache_key, ImageCache, PostImage, VariantFormat};
+
+    #[test]
+    fn image_variants() {
+        let config = BlogImageConfig {
+            widths: vec![960, 480],
+            webp: true,
+            ..Default::default()
+        };
+        let image = |width| PostImage {
+            url: "img/beach.jpg".into(),
+            path: "posts/trip/img/beach.jpg".into(),
+            virtual_path: "posts/trip/img/beach.jpg".into(),
+            width,
+            height: width / 2,
+            format: VariantFormat::Jpeg,
+            variants: PostImage::plan_variants(
+                Path::new("posts/trip/img/beach.jpg"),
+                width,
+                VariantFormat::Jpeg,
+                &config,
+            ),
+        };
+
+        let large = image(1200).to_responsive("/", "100vw");
+        assert_eq!(large.src, "/posts/trip/img/beach-960w.jpg");
+        assert_eq!(
+            large.srcset,
+            "/posts/trip/img/beach-480w.jpg 480w, /posts/trip/img/beach-960w.jpg 960w"
+        );
+        assert_eq!((large.width, large.height), (1200, 600));
+
+        let small = image(800);
+        assert_eq!(small.generated_variants().count(), 3);
+        let small = small.to_responsive("", "100vw");
+        assert_eq!(small.src, "/posts/trip/img/beach.jpg");
+        assert_eq!(
+            small.srcset,
+            "/posts/trip/img/beach-480w.jpg 480w, /posts/trip/img/beach.jpg 800w"
+        );
+        assert_eq!(
+            small.webp_srcset.as_deref(),
+            Some("/posts/trip/img/beach-480w.webp 480w, /posts/trip/img/beach-800w.webp 800w")
+        );
+    }
+
+    #[test]
+    fn image_cache_prune() {
+        let dir = tempdir().unwrap();
+        let cache = ImageCache {
+            directory: dir.path().to_path_buf(),
+            quality: 80,
+        };
+        let built = source_cache_key(Path::new("posts/trip/img/beach.jpg"));
+        let draft = source_cache_key(Path::new("posts/draft/img/map.png"));
+        let deleted = source_cache_key(Path::new("static/old.jpg"));
+        for name in [
+            format!("{built}-current.jpg"),
+            format!("{built}-outdated.jpg"),
+            format!("{draft}-unused.png"),
+            format!("{deleted}-unused.jpg"),
+        ] {
+            write(dir.path().join(name), "").unwrap();
+        }
+
+        let keys = HashSet::from([format!("{built}-current.jpg")]);
+        let sources = [
+            Path::new("posts/trip/img/beach.jpg"),
+            Path::new("posts/draft/img/map.png"),
+        ];
+        cache.prune(sources, &keys).unwrap();
+
+        let remaining = read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            remaining,
+            HashSet::from([
+                format!("{built}-current.jpg"),
+                format!("{draft}-unused.png")
+            ])
+        );
+    }
+}
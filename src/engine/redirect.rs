@@ -0,0 +1,94 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use super::xml::escape;
+
+/// Redirect from an alias of a post to its current url.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// Absolute path of the alias, e.g. `/posts/old_title.html`
+    pub from: String,
+    /// Absolute path of the post
+    pub to: String,
+}
+
+/// Render a page that sends browsers and crawlers on to the given url.
+pub fn render_redirect_page(url: &str) -> String {
+    let url = escape(url);
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>Redirecting…</title>
+    <link rel="canonical" href="{url}">
+    <meta name="robots" content="noindex">
+    <meta http-equiv="refresh" content="0; url={url}">
+</head>
+<body>
+    <p>This page has moved to <a href="{url}">{url}</a>.</p>
+</body>
+</html>
+"#
+    )
+}
+
+/// Render redirects in the `_redirects` format of Netlify and Cloudflare Pages.
+pub fn render_netlify_redirects(redirects: &[Redirect]) -> Result<String> {
+    let mut txt = String::new();
+    for redirect in redirects {
+        writeln!(txt, "{} {} 301", redirect.from, redirect.to)?;
+    }
+    Ok(txt)
+}
+
+/// Render redirects as an nginx map from request paths to their new location.
+pub fn render_nginx_map(redirects: &[Redirect]) -> Result<String> {
+    let mut conf = String::new();
+    writeln!(
+        conf,
+        "# Include in the http block and add to the server block:"
+    )?;
+    writeln!(
+        conf,
+        "# if ($crablog_redirect) {{ return 301 $crablog_redirect; }}"
+    )?;
+    writeln!(conf, "map $uri $crablog_redirect {{")?;
+    for redirect in redirects {
+        writeln!(conf, "    \"{}\" \"{}\";", redirect.from, redirect.to)?;
+    }
+    writeln!(conf, "}}")?;
+    Ok(conf)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::{render_netlify_redirects, render_nginx_map, render_redirect_page, Redirect};
+
+    #[test]
+    fn redirect_outputs() -> Result<()> {
+        let page = render_redirect_page("https://example.com/posts/new.html?a=1&b=2");
+        assert!(page.contains(
+            r#"<link rel="canonical" href="https://example.com/posts/new.html?a=1&amp;b=2">"#
+        ));
+        assert!(page.contains(
+            r#"<meta http-equiv="refresh" content="0; url=https://example.com/posts/new.html?a=1&amp;b=2">"#
+        ));
+
+        let redirects = vec![Redirect {
+            from: "/posts/old.html".into(),
+            to: "/2024/03/new/".into(),
+        }];
+        assert_eq!(
+            render_netlify_redirects(&redirects)?,
+            "/posts/old.html /2024/03/new/ 301\n"
+        );
+        assert!(render_nginx_map(&redirects)?.contains(
+            "map $uri $crablog_redirect {\n    \"/posts/old.html\" \"/2024/03/new/\";\n}"
+        ));
+        Ok(())
+    }
+}
@@ -1,12 +1,10 @@
 use anyhow::Result;
 use handlebars::Handlebars;
+use serde::Serialize;
 
-use crate::theme::ThemeLayout;
+use crate::{server::LIVE_RELOAD_ENDPOINT, theme::ThemeLayout};
 
-use super::{
-    data::{BaseDataBuilder, IndexPageData, PostPageData},
-    BuildEnvironment,
-};
+use super::{data::BaseDataBuilder, BuildEnvironment};
 
 #[derive(Debug)]
 pub struct Renderer<'a> {
@@ -20,38 +18,20 @@ impl<'a> Renderer<'a> {
         Self { env, base_layout }
     }
 
-    /// Render the index page.
-    pub fn render_index_page(
+    /// Render a page layout and embed it into the base layout.
+    pub fn render_page<T>(
         &self,
         source: impl AsRef<str>,
         base_data: BaseDataBuilder,
-        index_page_data: IndexPageData,
-    ) -> Result<String> {
+        page_data: T,
+    ) -> Result<String>
+    where
+        T: Serialize,
+    {
         let handlebars = Handlebars::new();
 
-        // Render index page template
-        let data = base_data.build(index_page_data);
-        let rendered_html = handlebars.render_template(source.as_ref(), &data)?;
-
-        // Render base template
-        let data = data.into_base_data().with_content(rendered_html);
-        let rendered_html = handlebars.render_template(self.base_layout.source.as_ref(), &data)?;
-
-        // Minify if production build
-        self.postprocess_html(rendered_html)
-    }
-
-    /// Render a post page.
-    pub fn render_post_page(
-        &self,
-        source: impl AsRef<str>,
-        base_data: BaseDataBuilder,
-        post_page_data: PostPageData,
-    ) -> Result<String> {
-        let handlebars = Handlebars::new();
-
-        // Render post page template
-        let data = base_data.build(post_page_data);
+        // Render page template
+        let data = base_data.build(page_data);
         let rendered_html = handlebars.render_template(source.as_ref(), &data)?;
 
         // Render base template
@@ -64,6 +44,7 @@ impl<'a> Renderer<'a> {
 
     /// Format output html file according to current environment.
     /// When building for production, the html source is minified.
+    /// When building for development, the live-reload client is injected.
     fn postprocess_html(&self, html: String) -> Result<String> {
         Ok(match self.env {
             // Minify HTML
@@ -72,9 +53,20 @@ impl<'a> Renderer<'a> {
                 let minified_bytes = minify_html::minify(html.as_bytes(), &cfg);
                 String::from_utf8(minified_bytes)?
             }
-            // Remove empty lines to get rid of artifacts from template rendering
-            // and improve readability of the rendered html source.
-            _ => html,
+            // Reload the page whenever the development server finished a rebuild
+            BuildEnvironment::Development => {
+                let snippet = format!(
+                    r#"<script>new EventSource("{LIVE_RELOAD_ENDPOINT}").onmessage = () => location.reload();</script>"#
+                );
+                match html.rfind("</body>") {
+                    Some(index) => {
+                        let mut html = html;
+                        html.insert_str(index, &snippet);
+                        html
+                    }
+                    None => html + &snippet,
+                }
+            }
         })
     }
 }
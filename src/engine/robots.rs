@@ -0,0 +1,49 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use crate::blog::config::BlogRobotsConfig;
+
+/// Render `robots.txt`, optionally pointing crawlers to the sitemap.
+pub fn render_robots_txt(config: &BlogRobotsConfig, sitemap_url: Option<&str>) -> Result<String> {
+    let mut txt = String::new();
+    writeln!(txt, "User-agent: {}", config.user_agent)?;
+    for path in &config.allow {
+        writeln!(txt, "Allow: {path}")?;
+    }
+    for path in &config.disallow {
+        writeln!(txt, "Disallow: {path}")?;
+    }
+    // An empty rule set would block nothing, but some crawlers expect a rule
+    if config.allow.is_empty() && config.disallow.is_empty() {
+        writeln!(txt, "Allow: /")?;
+    }
+    if let Some(sitemap_url) = sitemap_url {
+        writeln!(txt)?;
+        writeln!(txt, "Sitemap: {sitemap_url}")?;
+    }
+    Ok(txt)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use crate::blog::config::BlogRobotsConfig;
+
+    use super::render_robots_txt;
+
+    #[test]
+    fn robots_txt_with_sitemap() -> Result<()> {
+        let config = BlogRobotsConfig {
+            disallow: vec!["/drafts/".into()],
+            ..Default::default()
+        };
+        let txt = render_robots_txt(&config, Some("https://example.com/sitemap.xml"))?;
+        assert_eq!(
+            txt,
+            "User-agent: *\nDisallow: /drafts/\n\nSitemap: https://example.com/sitemap.xml\n"
+        );
+        Ok(())
+    }
+}
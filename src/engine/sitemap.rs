@@ -0,0 +1,51 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use super::xml::escape;
+
+/// Sitemap for search engine indexing.
+#[derive(Debug, Default)]
+pub struct Sitemap {
+    urls: Vec<SitemapUrl>,
+}
+
+#[derive(Debug)]
+struct SitemapUrl {
+    location: String,
+    last_modified: Option<NaiveDate>,
+}
+
+impl Sitemap {
+    /// Add an absolute url.
+    pub fn add_url(&mut self, location: String, last_modified: Option<NaiveDate>) {
+        self.urls.push(SitemapUrl {
+            location,
+            last_modified,
+        });
+    }
+
+    pub fn to_xml(&self) -> Result<String> {
+        let mut xml = String::new();
+        writeln!(xml, r#"<?xml version="1.0" encoding="utf-8"?>"#)?;
+        writeln!(
+            xml,
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#
+        )?;
+        for url in &self.urls {
+            writeln!(xml, "<url>")?;
+            writeln!(xml, "<loc>{}</loc>", escape(&url.location))?;
+            if let Some(last_modified) = url.last_modified {
+                writeln!(
+                    xml,
+                    "<lastmod>{}</lastmod>",
+                    last_modified.format("%Y-%m-%d")
+                )?;
+            }
+            writeln!(xml, "</url>")?;
+        }
+        write!(xml, "</urlset>")?;
+        Ok(xml)
+    }
+}
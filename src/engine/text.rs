@@ -0,0 +1,69 @@
+/// Strip tags from html and decode the basic entities, collapsing whitespace.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    decode_entities(&text)
+}
+
+fn decode_entities(text: &str) -> String {
+    [
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&quot;", "\""),
+        ("&#34;", "\""),
+        ("&#39;", "'"),
+        ("&#x27;", "'"),
+        ("&nbsp;", " "),
+        ("&amp;", "&"),
+    ]
+    .iter()
+    .fold(text.to_string(), |text, (entity, c)| {
+        text.replace(entity, c)
+    })
+}
+
+/// Shorten text to the given number of words, marking the cut with an ellipsis.
+pub fn truncate_words(text: &str, count: usize) -> String {
+    let words = text.split_whitespace().collect::<Vec<_>>();
+    let mut truncated = words
+        .iter()
+        .take(count)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if words.len() > count {
+        truncated.push('…');
+    }
+    truncated
+}
+
+#[cfg(test)]
+mod test {
+    use super::{html_to_text, truncate_words};
+
+    #[test]
+    fn text_html_to_text() {
+        assert_eq!(
+            html_to_text("<h1>Hello</h1><p>This is <em>a</em> test &amp; more!</p>"),
+            "Hello This is a test & more!"
+        );
+    }
+
+    #[test]
+    fn text_truncate_words() {
+        assert_eq!(truncate_words("one two three", 2), "one two…");
+        assert_eq!(truncate_words("one two", 2), "one two");
+    }
+}
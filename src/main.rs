@@ -2,19 +2,21 @@ mod blog;
 mod config;
 mod engine;
 mod logging;
+mod server;
 mod theme;
 mod traits;
 
 use anyhow::{anyhow, Context, Result};
 use blog::Post;
 use clap::{Parser, Subcommand};
-use std::fmt::Display;
+use std::{fmt::Display, path::PathBuf};
 use strum::IntoEnumIterator;
 
 use crate::{
     blog::Blog,
     config::{CommonProjectConfig, ConfigError},
     engine::{BuildEngine, BuildEnvironment},
+    server::DevServer,
     theme::Theme,
     traits::TryLoadConfig,
 };
@@ -25,10 +27,19 @@ enum Command {
     Init,
     /// Create a new post
     Post,
-    /// Build for development
-    Dev,
+    /// Serve for development with live-reload
+    #[command(alias = "serve")]
+    Dev {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+    },
     /// Build for production
-    Build,
+    Build {
+        /// Time to publish scheduled and expired posts for, e.g. "2024-03-01 12:00", instead of now
+        #[arg(long)]
+        now: Option<String>,
+    },
 }
 
 #[derive(Debug, Parser)]
@@ -37,6 +48,9 @@ enum Command {
 struct Args {
     #[command(subcommand)]
     command: Command,
+    /// Maximum number of threads used for building
+    #[arg(short, long, global = true)]
+    jobs: Option<usize>,
 }
 
 fn main() -> Result<()> {
@@ -44,16 +58,19 @@ fn main() -> Result<()> {
     logging::init()?;
 
     let args = Args::parse();
+    if let Some(jobs) = args.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()?;
+    }
+
     match args.command {
         Command::Init => create_new_project()?,
         Command::Post => create_new_post()?,
-        Command::Dev => {
+        Command::Dev { port } => serve(port)?,
+        Command::Build { now } => {
             let config = load_config()?;
-            build(config, BuildEnvironment::Development)?
-        }
-        Command::Build => {
-            let config = load_config()?;
-            build(config, BuildEnvironment::Production)?
+            build(config, BuildEnvironment::Production, now)?
         }
     }
 
@@ -149,11 +166,37 @@ fn create_new_post() -> Result<()> {
     let post_name = dialoguer::Input::<String>::new()
         .with_prompt("Post Title")
         .interact_text()?;
-    Post::scaffold(post_name.into())
+    // Posts are created in the timezone of the blog they are written for
+    let timezone = load_config()?
+        .blog_config
+        .map(|config| config.timezone)
+        .unwrap_or_default();
+    Post::scaffold(post_name.into(), timezone)
 }
 
-fn build(config: CommonProjectConfig, env: BuildEnvironment) -> Result<()> {
+fn build(config: CommonProjectConfig, env: BuildEnvironment, now: Option<String>) -> Result<()> {
     let blog = config.to_blog()?;
+    let blog = match now {
+        Some(now) => {
+            let now = blog::parse_datetime(&now, blog.config().timezone)
+                .with_context(|| format!("Invalid value {now:?} for `--now`"))?;
+            blog.with_build_time(now)
+        }
+        None => blog,
+    };
     let mut engine = BuildEngine::new(env, blog);
     engine.build()
 }
+
+fn serve(port: u16) -> Result<()> {
+    fn build_for_development() -> Result<Vec<PathBuf>> {
+        let blog = load_config()?.to_blog()?;
+        let watch_paths = blog.source_paths();
+        let mut engine = BuildEngine::new(BuildEnvironment::Development, blog);
+        engine.build()?;
+        Ok(watch_paths)
+    }
+
+    let watch_paths = build_for_development()?;
+    DevServer::new(port).run(watch_paths, build_for_development)
+}
@@ -0,0 +1,7 @@
+mod dev_server;
+mod file_watcher;
+mod live_reload;
+
+pub use dev_server::DevServer;
+pub use file_watcher::FileWatcher;
+pub use live_reload::{LiveReload, LIVE_RELOAD_ENDPOINT};
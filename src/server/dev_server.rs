@@ -0,0 +1,250 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use anyhow::{anyhow, Result};
+use tiny_http::{Header, Request, Response, Server};
+
+use super::{FileWatcher, LiveReload, LIVE_RELOAD_ENDPOINT};
+
+/// Local HTTP server for the build output with live-reload.
+#[derive(Debug)]
+pub struct DevServer {
+    address: String,
+    root: PathBuf,
+    live_reload: LiveReload,
+}
+
+impl DevServer {
+    pub fn new(port: u16) -> Self {
+        Self {
+            address: format!("127.0.0.1:{port}"),
+            root: PathBuf::from("./build"),
+            live_reload: LiveReload::default(),
+        }
+    }
+
+    /// Serve the build directory and rebuild whenever a watched path changes.
+    ///
+    /// `rebuild` is called for every change and returns the paths
+    /// to watch from then on. Open pages are reloaded after each
+    /// successful rebuild. This function only returns on error.
+    pub fn run<F>(self, watch_paths: Vec<PathBuf>, mut rebuild: F) -> Result<()>
+    where
+        F: FnMut() -> Result<Vec<PathBuf>>,
+    {
+        let server = Server::http(&self.address)
+            .map_err(|error| anyhow!("Unable to listen on {}: {error}", self.address))?;
+        println!("Serving your blog at http://{}", self.address);
+
+        // Handle requests in the background
+        let root = self.root.clone();
+        let live_reload = self.live_reload.clone();
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let root = root.clone();
+                let live_reload = live_reload.clone();
+                // Event streams stay open, so every request gets its own thread
+                thread::spawn(move || {
+                    if let Err(error) = handle_request(request, &root, &live_reload) {
+                        log::debug!("Unable to handle request: {error:#}");
+                    }
+                });
+            }
+        });
+
+        // Rebuild on change
+        let mut watcher = FileWatcher::new(watch_paths)?;
+        loop {
+            let changed_paths = watcher.wait_for_changes()?;
+            log::debug!("Changed: {changed_paths:?}");
+            println!("Change detected, rebuilding");
+            match rebuild() {
+                Ok(watch_paths) => {
+                    watcher.watch(watch_paths)?;
+                    self.live_reload.reload();
+                }
+                Err(error) => log::error!("Build failed: {error:#}"),
+            }
+        }
+    }
+}
+
+fn handle_request(request: Request, root: &Path, live_reload: &LiveReload) -> Result<()> {
+    let url_path = request
+        .url()
+        .split(['?', '#'])
+        .next()
+        .map(percent_decode)
+        .unwrap_or_default();
+
+    if url_path == LIVE_RELOAD_ENDPOINT {
+        return stream_reload_events(request, live_reload);
+    }
+
+    match resolve_file(root, &url_path) {
+        Some(path) => {
+            let content_type = Header::from_bytes("Content-Type", content_type(&path))
+                .map_err(|_| anyhow!("Invalid content type header"))?;
+            let response = Response::from_file(File::open(&path)?).with_header(content_type);
+            request.respond(response)?;
+        }
+        None => {
+            log::debug!("Not found: {url_path}");
+            request.respond(Response::from_string("Not found").with_status_code(404))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Keep the connection open and send a server-sent event for every reload.
+fn stream_reload_events(request: Request, live_reload: &LiveReload) -> Result<()> {
+    let reloads = live_reload.subscribe();
+    let mut writer = request.into_writer();
+    writer.write_all(
+        b"HTTP/1.1 200 OK\r\n\
+        Content-Type: text/event-stream\r\n\
+        Cache-Control: no-cache\r\n\
+        Connection: keep-alive\r\n\r\n\
+        retry: 1000\n\n",
+    )?;
+    writer.flush()?;
+    while reloads.recv().is_ok() {
+        writer.write_all(b"data: reload\n\n")?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Map an url path to a file in the build directory.
+fn resolve_file(root: &Path, url_path: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    for segment in url_path.split('/').filter(|segment| !segment.is_empty()) {
+        // Never serve anything outside of the build directory
+        if segment == ".." || segment.contains('\\') {
+            return None;
+        }
+        path.push(segment);
+    }
+    if path.is_dir() {
+        path.push("index.html");
+    }
+    path.is_file().then_some(path)
+}
+
+fn percent_decode(str: &str) -> String {
+    let bytes = str.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "txt" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        fs::{create_dir_all, write},
+        path::Path,
+    };
+
+    use tempfile::tempdir;
+
+    use super::{content_type, percent_decode, resolve_file};
+
+    #[test]
+    fn dev_server_percent_decode() {
+        assert_eq!(percent_decode("/hello%20world.html"), "/hello world.html");
+        assert_eq!(percent_decode("/caf%C3%A9"), "/café");
+        assert_eq!(percent_decode("/%2e%2e/secret"), "/../secret");
+        // Invalid escapes are kept as they are
+        assert_eq!(percent_decode("/100%"), "/100%");
+        assert_eq!(percent_decode("/%zz%4"), "/%zz%4");
+        assert_eq!(percent_decode("/%+1"), "/%+1");
+    }
+
+    #[test]
+    fn dev_server_resolve_file() {
+        let build_dir = tempdir().unwrap();
+        let root = build_dir.path();
+        create_dir_all(root.join("posts")).unwrap();
+        write(root.join("index.html"), "").unwrap();
+        write(root.join("posts/hello.html"), "").unwrap();
+        write(root.join("posts/index.html"), "").unwrap();
+
+        assert_eq!(resolve_file(root, "/"), Some(root.join("index.html")));
+        assert_eq!(
+            resolve_file(root, "/posts"),
+            Some(root.join("posts/index.html"))
+        );
+        assert_eq!(
+            resolve_file(root, "//posts//hello.html"),
+            Some(root.join("posts/hello.html"))
+        );
+        assert_eq!(resolve_file(root, "/missing.html"), None);
+        assert_eq!(resolve_file(root, "/posts/../index.html"), None);
+        assert_eq!(resolve_file(root, "/.."), None);
+        assert_eq!(
+            resolve_file(root, &percent_decode("/%2e%2e/index.html")),
+            None
+        );
+        assert_eq!(resolve_file(root, "/..\\index.html"), None);
+    }
+
+    #[test]
+    fn dev_server_content_type() {
+        assert_eq!(
+            content_type(Path::new("index.html")),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(content_type(Path::new("photo.JPG")), "image/jpeg");
+        assert_eq!(
+            content_type(Path::new("README")),
+            "application/octet-stream"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    path::PathBuf,
+    sync::mpsc::{channel, Receiver},
+    time::Duration,
+};
+
+use anyhow::Result;
+use itertools::Itertools;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Time to wait for follow-up events before reporting a change.
+/// Editors tend to emit several events for a single save.
+const DEBOUNCE_DURATION: Duration = Duration::from_millis(100);
+
+/// Watches project sources for changes.
+pub struct FileWatcher {
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    watched_paths: Vec<PathBuf>,
+}
+
+impl FileWatcher {
+    pub fn new(paths: Vec<PathBuf>) -> Result<Self> {
+        let (sender, events) = channel();
+        let watcher = notify::recommended_watcher(sender)?;
+        let mut file_watcher = Self {
+            watcher,
+            events,
+            watched_paths: Vec::new(),
+        };
+        file_watcher.watch(paths)?;
+        Ok(file_watcher)
+    }
+
+    /// Replace the set of watched paths.
+    ///
+    /// Paths that don't exist are skipped. Directories are watched recursively.
+    pub fn watch(&mut self, paths: Vec<PathBuf>) -> Result<()> {
+        // Editors often save by replacing files, which silently drops
+        // the watch on the original file. Re-registering fixes that.
+        for path in self.watched_paths.drain(..) {
+            let _ = self.watcher.unwatch(&path);
+        }
+        for path in paths {
+            if !path.exists() {
+                log::debug!("Not watching {path:?}: Path does not exist");
+                continue;
+            }
+            let mode = if path.is_dir() {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            log::debug!("Watching {path:?}");
+            self.watcher.watch(&path, mode)?;
+            self.watched_paths.push(path);
+        }
+        Ok(())
+    }
+
+    /// Block until something changed and return the changed paths.
+    pub fn wait_for_changes(&self) -> Result<Vec<PathBuf>> {
+        let mut changed_paths = Vec::new();
+        while changed_paths.is_empty() {
+            Self::collect_paths(self.events.recv()?, &mut changed_paths);
+        }
+        while let Ok(event) = self.events.recv_timeout(DEBOUNCE_DURATION) {
+            Self::collect_paths(event, &mut changed_paths);
+        }
+        Ok(changed_paths.into_iter().unique().collect())
+    }
+
+    fn collect_paths(event: notify::Result<Event>, paths: &mut Vec<PathBuf>) {
+        match event {
+            Ok(event) if !event.kind.is_access() => paths.extend(event.paths),
+            Ok(_) => (),
+            Err(error) => log::warn!("Unable to watch for changes: {error}"),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::sync::{
+    mpsc::{channel, Receiver, Sender},
+    Arc, Mutex,
+};
+
+/// URL path of the event stream that announces finished rebuilds.
+pub const LIVE_RELOAD_ENDPOINT: &str = "/__crablog/live-reload";
+
+/// Broadcasts reload events to all connected browser tabs.
+#[derive(Debug, Clone, Default)]
+pub struct LiveReload {
+    subscribers: Arc<Mutex<Vec<Sender<()>>>>,
+}
+
+impl LiveReload {
+    /// Register a new client.
+    ///
+    /// The returned receiver yields a message for every reload
+    /// and is disconnected when the server shuts down.
+    pub fn subscribe(&self) -> Receiver<()> {
+        let (sender, receiver) = channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(sender);
+        }
+        receiver
+    }
+
+    /// Ask all connected clients to reload.
+    pub fn reload(&self) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            // Drop clients that went away in the meantime
+            subscribers.retain(|subscriber| subscriber.send(()).is_ok());
+            log::debug!("Sent reload event to {} client(s)", subscribers.len());
+        }
+    }
+}
@@ -12,12 +12,22 @@ use super::{config::ThemeConfig, ThemeBundle};
 pub struct Theme {
     config: ThemeConfig,
     bundle: ThemeBundle,
+    path: PathBuf,
 }
 
 impl Theme {
     pub fn from_config(config: ThemeConfig, path: PathBuf) -> Result<Self> {
         let bundle = ThemeBundle::load_from_path(&path)?;
-        Ok(Self { config, bundle })
+        Ok(Self {
+            config,
+            bundle,
+            path,
+        })
+    }
+
+    /// Get the directory the theme was loaded from.
+    pub fn path(&self) -> &Path {
+        &self.path
     }
 
     pub fn bundle(&self) -> &ThemeBundle {
@@ -35,6 +45,7 @@ impl Theme {
         create_dir_all(path.join("layouts"))?;
         create_dir_all(path.join("styles"))?;
         create_dir_all(path.join("scripts"))?;
+        create_dir_all(path.join("assets"))?;
 
         Ok(())
     }
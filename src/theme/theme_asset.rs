@@ -1,2 +1,38 @@
+use std::{
+    fs::read,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+/// A static file shipped with a theme, e.g. a font, image or favicon.
 #[derive(Debug)]
-pub struct ThemeAsset {}
+pub struct ThemeAsset {
+    path: PathBuf,
+    virtual_path: PathBuf,
+}
+
+impl ThemeAsset {
+    /// Create an asset from its path and the `assets/` directory containing it.
+    pub fn new(path: PathBuf, assets_path: impl AsRef<Path>) -> Result<Self> {
+        let virtual_path = path
+            .strip_prefix(assets_path)
+            .with_context(|| format!("Asset {path:?} is outside of the assets directory"))?
+            .to_path_buf();
+        Ok(Self { path, virtual_path })
+    }
+
+    /// Get the path of the source file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the path of the asset inside the build, relative to the `assets/` directory.
+    pub fn virtual_path(&self) -> &Path {
+        &self.virtual_path
+    }
+
+    pub fn read(&self) -> Result<Vec<u8>> {
+        read(&self.path).with_context(|| format!("Unable to read asset {:?}", self.path))
+    }
+}
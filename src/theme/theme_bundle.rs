@@ -13,7 +13,7 @@ const EXT_STYLES: [&str; 1] = ["css"];
 
 #[derive(Debug)]
 pub struct ThemeBundle {
-    _assets: Vec<ThemeAsset>,
+    assets: Vec<ThemeAsset>,
     layouts: Vec<ThemeLayout>,
     scripts: Vec<ThemeScript>,
     styles: Vec<ThemeStyle>,
@@ -24,7 +24,15 @@ impl ThemeBundle {
         self.layouts
             .iter()
             .find(|layout| layout.kind == kind)
-            .context("Unable to find index layout")
+            .with_context(|| format!("Unable to find {kind:?} layout"))
+    }
+
+    pub fn has_layout(&self, kind: LayoutKind) -> bool {
+        self.layouts.iter().any(|layout| layout.kind == kind)
+    }
+
+    pub fn get_layouts(&self) -> &Vec<ThemeLayout> {
+        &self.layouts
     }
 
     pub fn get_styles(&self) -> &Vec<ThemeStyle> {
@@ -35,19 +43,37 @@ impl ThemeBundle {
         &self.scripts
     }
 
+    pub fn get_assets(&self) -> &Vec<ThemeAsset> {
+        &self.assets
+    }
+
     pub fn load_from_path(path: impl AsRef<Path>) -> Result<Self> {
         log::debug!("Loading theme bundle from {:?}", path.as_ref());
         let layouts = Self::load(&path, "layouts", &EXT_LAYOUTS);
         let scripts = Self::load(&path, "scripts", &EXT_SCRIPTS);
         let styles = Self::load(&path, "styles", &EXT_STYLES);
+        let assets = Self::load_assets(&path)?;
         Ok(Self {
+            assets,
             layouts,
             scripts,
             styles,
-            _assets: Default::default(),
         })
     }
 
+    /// Load all files in the `assets/` directory, regardless of their type.
+    fn load_assets(base_path: impl AsRef<Path>) -> Result<Vec<ThemeAsset>> {
+        let path = base_path.as_ref().join("assets");
+        log::debug!("Loading assets from {path:?}");
+        WalkDir::new(&path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| ThemeAsset::new(entry.into_path(), &path))
+            .collect()
+    }
+
     fn load<T>(
         base_path: impl AsRef<Path>,
         dir: impl AsRef<str>,
@@ -1,17 +1,21 @@
 use anyhow::{bail, Context, Result};
-use std::{
-    borrow::Cow,
-    fs::read_to_string,
-    path::{Path, PathBuf},
-};
+use std::{borrow::Cow, fs::read_to_string, path::Path};
 
 use crate::traits::TryFromFile;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LayoutKind {
     Base,
     Index,
     Post,
+    /// Standalone page, e.g. an about page
+    Page,
+    /// Overview of all terms of a taxonomy
+    Taxonomy,
+    /// Listing of all posts of a single taxonomy term
+    Term,
+    /// Profile of an author with all their posts
+    Author,
 }
 
 impl LayoutKind {
@@ -27,14 +31,19 @@ impl LayoutKind {
             "_base" | "base" => Self::Base,
             "index" => Self::Index,
             "post" => Self::Post,
-            _ => bail!("Unknown file name. Must match one of ['index', 'post']."),
+            "page" => Self::Page,
+            "taxonomy" => Self::Taxonomy,
+            "term" => Self::Term,
+            "author" => Self::Author,
+            _ => {
+                bail!("Unknown file name. Must match one of ['index', 'post', 'page', 'taxonomy', 'term', 'author'].")
+            }
         })
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ThemeLayout {
-    pub path: PathBuf,
     pub source: String,
     pub kind: LayoutKind,
 }
@@ -46,10 +55,6 @@ impl TryFromFile for ThemeLayout {
     {
         let source = read_to_string(&path)?;
         let kind = LayoutKind::from_file_name(&path)?;
-        Ok(Self {
-            path: path.into(),
-            source,
-            kind,
-        })
+        Ok(Self { source, kind })
     }
 }
@@ -5,27 +5,16 @@ use serde::Serialize;
 use crate::config::{ConfigError, CONFIG_FILENAME};
 
 pub trait TrySaveConfig {
-    /// Try to write the config file to the current directory.
-    fn try_save(&self) -> Result<(), ConfigError>
-    where
-        Self: Serialize,
-    {
-        let path = Path::new(CONFIG_FILENAME);
-        log::debug!("Saving config to {:?}", path);
-        let toml = toml::to_string_pretty(self)?;
-        write(path, toml)?;
-        Ok(())
-    }
-
     /// Try to write the config file to the specified directory.
     fn try_save_to(&self, path: Cow<Path>) -> Result<(), ConfigError>
     where
         Self: Serialize,
     {
-        let path = path
-            .ends_with(CONFIG_FILENAME)
-            .then(|| path.to_path_buf())
-            .unwrap_or_else(|| path.join(CONFIG_FILENAME));
+        let path = if path.ends_with(CONFIG_FILENAME) {
+            path.to_path_buf()
+        } else {
+            path.join(CONFIG_FILENAME)
+        };
         log::debug!("Saving config to {:?}", path);
         let toml = toml::to_string_pretty(self)?;
         write(path, toml)?;